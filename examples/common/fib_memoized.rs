@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use crate::Benchmark;
+use crate::{fib::check, Benchmark};
 
 pub fn memoized(n: u64, table: &mut HashMap<u64, u64>) -> u64 {
     match n {
@@ -26,7 +26,7 @@ impl Benchmark<u64> for Memoized {
     fn run(&mut self, inp: &u64) -> Self::Res {
         memoized(*inp, &mut self.0)
     }
-    fn teardown(&mut self, _inp: &u64, _res: Self::Res) {
-        // assert_eq!(ANS[*inp as usize], res, "input: {inp}");
+    fn verify(&mut self, inp: &u64, res: &Self::Res) -> Result<(), &'static str> {
+        check(*inp, *res)
     }
 }
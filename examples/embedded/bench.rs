@@ -56,7 +56,10 @@ fn main() -> ! {
 
     let mut core_p = hal::CorePeripherals::take().unwrap();
     let mut m = CortexMCycleCount::new(&mut core_p.DWT, &mut core_p.DCB).unwrap();
-    let mut r = BasicReporter::new_with_serial::<u8, _, _>(&mut *uart);
+    // Frames are much shorter than the text report; `xtask` decodes them (and
+    // can save and compare the results, with `--report`).
+    let mut frames = FrameLines::new(&mut *uart);
+    let mut r = BinaryReporter::new(&mut frames);
 
     BenchmarkRunner::new()
         .set_iterations(20)
@@ -66,7 +69,9 @@ fn main() -> ! {
                 .add("iterative", Iterative)
                 .add("closed form", ClosedForm),
         )
-        .run(&mut m, &mut r);
+        .run(&mut m, &mut r)
+        .map_err(|_| ())
+        .unwrap();
 
     writeln!(uart, "\n{}", END_DELIM).unwrap();
 
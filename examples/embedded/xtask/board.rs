@@ -0,0 +1,159 @@
+use std::{fs, io, path::Path};
+
+use serde::Deserialize;
+
+/// The board `xtask` runs things on: how to flash it and where to find its
+/// serial port.
+///
+/// Read from `board.toml` (next to the example's `Cargo.toml`) or from the
+/// file given with `--board`/`$UBENCH_BOARD`; without one this is the TM4C
+/// the example was written for.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Board {
+    #[serde(default = "Board::default_name")]
+    pub name: String,
+    #[serde(default)]
+    pub flasher: Flasher,
+    #[serde(default)]
+    pub serial: Serial,
+    #[serde(default)]
+    pub gdb: Gdb,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum Flasher {
+    /// TI's ICDI, on TM4C LaunchPads; fetched if it's not on `$PATH`.
+    #[default]
+    Lm4flash,
+    /// `probe-rs download` then `probe-rs reset`.
+    ProbeRs {
+        chip: String,
+        /// `VID:PID[:serial]`, for when there's more than one probe.
+        probe: Option<String>,
+    },
+    /// OpenOCD's `program` command; `files` are the config files to load (`-f`)
+    /// i.e. `["interface/stlink.cfg", "target/stm32f4x.cfg"]`.
+    Openocd { files: Vec<String> },
+    /// Any other command; `{elf}` and `{bin}` (a raw binary, made with
+    /// `objcopy`) in the arguments are replaced with the program's paths.
+    Custom { command: Vec<String> },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Serial {
+    /// Skips looking for the port.
+    pub port: Option<String>,
+    #[serde(default = "Serial::default_baud")]
+    pub baud: u32,
+    /// What to look for when `port` isn't set; without this the first USB
+    /// serial port is used.
+    pub usb: Option<Usb>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Usb {
+    pub vid: u16,
+    pub pid: u16,
+}
+
+/// How `--debug` runs GDB.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Gdb {
+    /// The GDB server to start; `{elf}` and `{port}` in the arguments are
+    /// replaced. By default this is OpenOCD or `probe-rs gdb`, depending on
+    /// the flasher.
+    pub server: Option<Vec<String>>,
+    /// The (local) port the server listens on; 3333 for OpenOCD and 1337 for
+    /// `probe-rs` by default.
+    pub port: Option<u16>,
+    /// The GDB to run; by default the first of `arm-none-eabi-gdb`,
+    /// `gdb-multiarch`, and `gdb` that's on `$PATH`.
+    pub client: Option<String>,
+    /// Run before loading the program, i.e. `monitor reset halt`.
+    #[serde(default)]
+    pub commands: Vec<String>,
+    /// Whether GDB should flash the program (`load`); servers that are given
+    /// the program (i.e. QEMU's) don't need this.
+    #[serde(default = "Gdb::default_load")]
+    pub load: bool,
+    #[serde(default = "Gdb::default_breakpoint")]
+    pub breakpoint: String,
+}
+
+impl Default for Gdb {
+    fn default() -> Self {
+        Gdb {
+            server: None,
+            port: None,
+            client: None,
+            commands: Vec::new(),
+            load: Self::default_load(),
+            breakpoint: Self::default_breakpoint(),
+        }
+    }
+}
+
+impl Gdb {
+    fn default_load() -> bool {
+        true
+    }
+
+    fn default_breakpoint() -> String {
+        "main".to_string()
+    }
+}
+
+impl Default for Serial {
+    fn default() -> Self {
+        Serial {
+            port: None,
+            baud: Self::default_baud(),
+            usb: None,
+        }
+    }
+}
+
+impl Serial {
+    fn default_baud() -> u32 {
+        1_500_000
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Board {
+            name: "TM4C".to_string(),
+            flasher: Flasher::default(),
+            serial: Serial {
+                // The TM4C's ICDI:
+                usb: Some(Usb {
+                    vid: 0x1cbe,
+                    pid: 0x00fd,
+                }),
+                ..Serial::default()
+            },
+            gdb: Gdb::default(),
+        }
+    }
+}
+
+impl Board {
+    fn default_name() -> String {
+        "board".to_string()
+    }
+
+    /// Reads the board file at `path`; a missing file is only an error if
+    /// `required` is set (i.e. the path was given explicitly).
+    pub fn load(path: &Path, required: bool) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(s) => toml::from_str(&s).map_err(|e| format!("in `{}`: {e}", path.display())),
+            Err(e) if e.kind() == io::ErrorKind::NotFound && !required => Ok(Board::default()),
+            Err(e) => Err(format!("couldn't read `{}`: {e}", path.display())),
+        }
+    }
+}
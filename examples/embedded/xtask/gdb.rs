@@ -0,0 +1,230 @@
+//! `--debug`: starts a GDB server for the board and runs GDB against it.
+
+use std::{
+    fs::{self, File},
+    net::{Ipv4Addr, SocketAddr, TcpStream},
+    path::Path,
+    process::{Child, Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use owo_colors::OwoColorize;
+use which::which;
+
+use crate::board::{Board, Flasher};
+
+const OPENOCD_PORT: u16 = 3333;
+const PROBE_RS_PORT: u16 = 1337;
+
+/// Kills the server when dropped, so it doesn't outlive us (on errors too).
+struct Server(Child);
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn error(msg: impl std::fmt::Display) {
+    eprintln!("{}: {msg}", "error".red().bold());
+}
+
+pub fn debug(elf: &Path, board: &Board) -> Result<(), u32> {
+    let gdb = &board.gdb;
+    let (server, port, openocd) = match server(board) {
+        Some(s) => s,
+        None => {
+            error("don't know which GDB server to use for a custom flasher; set `gdb.server`");
+            return Err(5);
+        }
+    };
+    if server.is_empty() {
+        error("the board's `gdb.server` is empty");
+        return Err(5);
+    }
+    let server: Vec<String> = server
+        .iter()
+        .map(|a| {
+            a.replace("{elf}", &elf.to_string_lossy())
+                .replace("{port}", &port.to_string())
+        })
+        .collect();
+
+    let client = match &gdb.client {
+        Some(c) => which(c).ok(),
+        None => ["arm-none-eabi-gdb", "gdb-multiarch", "gdb"]
+            .iter()
+            .find_map(|g| which(g).ok()),
+    };
+    let client = client.ok_or_else(|| {
+        let name = gdb.client.as_deref().unwrap_or("arm-none-eabi-gdb");
+        error(format!("couldn't find `{}` on $PATH", name.bold()));
+        4u32
+    })?;
+
+    // The server's output would get mixed up with GDB's:
+    let log_path = elf.with_extension("gdb-server.log");
+    let log = File::create(&log_path).map_err(|e| {
+        error(format!("couldn't create `{}`: {e}", log_path.display()));
+        5u32
+    })?;
+    let log_err = log.try_clone().map_err(|e| {
+        error(format!("couldn't open `{}`: {e}", log_path.display()));
+        5u32
+    })?;
+
+    eprintln!(
+        "{:>12} {} {}",
+        "Starting".cyan().bold(),
+        server.join(" "),
+        format!("(logging to {})", log_path.display()).dimmed(),
+    );
+    let mut command = Command::new(&server[0]);
+    command
+        .args(&server[1..])
+        .stdin(Stdio::null())
+        .stdout(log)
+        .stderr(log_err);
+    // Ctrl-C in GDB interrupts the target; the server shouldn't get it too.
+    in_own_process_group(&mut command);
+    let mut server = match command.spawn() {
+        Ok(child) => Server(child),
+        Err(e) => {
+            error(format!("couldn't start `{}`: {e}", server[0]));
+            return Err(4);
+        }
+    };
+
+    wait_for_server(&mut server, port, &log_path)?;
+
+    // Leave Ctrl-C to GDB; we'll clean up once it exits.
+    let _ = ctrlc::set_handler(|| {});
+
+    let mut args = vec![
+        "-q".to_string(),
+        "-ex".into(),
+        "set print asm-demangle on".into(),
+        "-ex".into(),
+        format!("target remote localhost:{port}"),
+    ];
+    if openocd {
+        args.extend(["-ex".into(), "monitor arm semihosting enable".into()]);
+    }
+    for c in &gdb.commands {
+        args.extend(["-ex".into(), c.clone()]);
+    }
+    if gdb.load {
+        args.extend(["-ex".into(), "load".into()]);
+    }
+    args.extend([
+        "-ex".into(),
+        format!("break {}", gdb.breakpoint),
+        "-ex".into(),
+        "break rust_begin_unwind".into(),
+        "-ex".into(),
+        "continue".into(),
+    ]);
+
+    let status = Command::new(&client).args(&args).arg(elf).status();
+    drop(server);
+
+    match status {
+        Ok(s) if s.success() => Ok(()),
+        Ok(s) => Err(s.code().unwrap_or(1) as u32),
+        Err(e) => {
+            error(format!("couldn't run `{}`: {e}", client.display()));
+            Err(4)
+        }
+    }
+}
+
+/// The server's command (with placeholders), its port, and whether it's
+/// OpenOCD.
+fn server(board: &Board) -> Option<(Vec<String>, u16, bool)> {
+    let gdb = &board.gdb;
+    if let Some(s) = &gdb.server {
+        let openocd = matches!(
+            s.first(), Some(p) if Path::new(p).file_stem() == Some("openocd".as_ref())
+        );
+        return Some((s.clone(), gdb.port.unwrap_or(OPENOCD_PORT), openocd));
+    }
+
+    let openocd = |files: &[&str]| {
+        let mut cmd = vec!["openocd".to_string()];
+        for f in files {
+            cmd.extend(["-f".to_string(), f.to_string()]);
+        }
+        cmd.extend(["-c".to_string(), "gdb_port {port}".to_string()]);
+        (cmd, gdb.port.unwrap_or(OPENOCD_PORT), true)
+    };
+
+    Some(match &board.flasher {
+        Flasher::Lm4flash => openocd(&["board/ti_ek-tm4c123gxl.cfg"]),
+        Flasher::Openocd { files } => openocd(&files.iter().map(|f| &**f).collect::<Vec<_>>()),
+        Flasher::ProbeRs { chip, probe } => {
+            let mut cmd = vec![
+                "probe-rs".to_string(),
+                "gdb".into(),
+                "--chip".into(),
+                chip.clone(),
+            ];
+            if let Some(p) = probe {
+                cmd.extend(["--probe".into(), p.clone()]);
+            }
+            cmd.extend(["--gdb-connection-string".into(), "127.0.0.1:{port}".into()]);
+            (cmd, gdb.port.unwrap_or(PROBE_RS_PORT), false)
+        }
+        Flasher::Custom { .. } => return None,
+    })
+}
+
+fn wait_for_server(server: &mut Server, port: u16, log: &Path) -> Result<(), u32> {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let start = Instant::now();
+
+    loop {
+        if let Ok(Some(status)) = server.0.try_wait() {
+            error(format!("the GDB server exited ({status}):"));
+            eprint!("{}", fs::read_to_string(log).unwrap_or_default());
+            return Err(4);
+        }
+        if TcpStream::connect_timeout(&addr, Duration::from_millis(100)).is_ok() {
+            return Ok(());
+        }
+        if start.elapsed() > Duration::from_secs(10) {
+            error(format!(
+                "the GDB server isn't listening on port {port}; see `{}`",
+                log.display()
+            ));
+            return Err(4);
+        }
+
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+#[cfg(unix)]
+fn in_own_process_group(command: &mut Command) {
+    use std::os::unix::process::CommandExt;
+
+    // Safety: `setpgid` is async-signal-safe.
+    unsafe {
+        command.pre_exec(|| {
+            if libc::setpgid(0, 0) == 0 {
+                Ok(())
+            } else {
+                Err(std::io::Error::last_os_error())
+            }
+        });
+    }
+}
+
+#[cfg(windows)]
+fn in_own_process_group(command: &mut Command) {
+    use std::os::windows::process::CommandExt;
+
+    const CREATE_NEW_PROCESS_GROUP: u32 = 0x200;
+    command.creation_flags(CREATE_NEW_PROCESS_GROUP);
+}
@@ -0,0 +1,338 @@
+//! `--bench --report`: collects the device's results so they can be saved and
+//! compared against an earlier run.
+//!
+//! Results come from [`BinaryReporter`](ubench::reporters::BinaryReporter)
+//! frames (sent as `ubench-frame [..]` lines, i.e. with a
+//! [`FrameLines`](ubench::reporters::FrameLines)) or from JSON events, one per
+//! run:
+//!
+//! ```json
+//! { "benchmark": "fib/iterative", "input": "20", "measurement": 1234 }
+//! ```
+
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    fmt::{self, Debug},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use owo_colors::OwoColorize;
+use serde::{Deserialize, Serialize};
+use ubench::{device::Value, metrics::RawUnit, Metric, Reporter};
+
+/// What the example measures on the device; only used to decode (and print)
+/// what it sends.
+pub struct Cycles;
+
+impl Metric for Cycles {
+    type Unit = u64;
+    type Divisor = u64;
+    type Start = ();
+
+    const UNIT_NAME: &'static str = "cycles";
+
+    fn start(&mut self) {
+        unreachable!("host side only")
+    }
+    fn end(&mut self, (): ()) -> u64 {
+        unreachable!("host side only")
+    }
+}
+
+#[derive(Debug)]
+pub struct ReportOptions {
+    /// Where to write the results.
+    pub save: PathBuf,
+    pub baseline: Option<Results>,
+    /// How much slower (in percent) a benchmark can get before it counts as a
+    /// regression.
+    pub threshold: f64,
+}
+
+impl ReportOptions {
+    /// Results go next to the binary by default, i.e.
+    /// `target/<triple>/release/ubench/bench.json`.
+    pub fn default_save_path(bin: &Path) -> PathBuf {
+        let mut dir = bin.parent().unwrap_or(Path::new("."));
+        if dir.ends_with("deps") {
+            dir = dir.parent().unwrap_or(dir);
+        }
+
+        dir.join("ubench")
+            .join(format!("{}.json", crate::bin_name(bin)))
+    }
+}
+
+/// The aggregated measurements for each (benchmark, input) pair.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Results {
+    pub board: String,
+    pub unit: String,
+    pub results: Vec<Entry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Entry {
+    /// `suite/benchmark` for benchmarks in suites.
+    pub benchmark: String,
+    /// As `Debug` text.
+    pub input: String,
+    pub iterations: u64,
+    pub min: u64,
+    pub max: u64,
+    pub mean: f64,
+}
+
+impl Results {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let s = fs::read_to_string(path)
+            .map_err(|e| format!("couldn't read `{}`: {e}", path.display()))?;
+        serde_json::from_str(&s).map_err(|e| format!("in `{}`: {e}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let err = |e: &dyn fmt::Display| format!("couldn't write `{}`: {e}", path.display());
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| err(&e))?;
+        }
+
+        let s = serde_json::to_string_pretty(self).map_err(|e| err(&e))?;
+        fs::write(path, s + "\n").map_err(|e| err(&e))
+    }
+
+    /// Prints how each benchmark changed since `baseline` and returns the
+    /// number of regressions (benchmarks whose mean got more than `threshold`
+    /// percent worse).
+    pub fn compare(&self, baseline: &Results, threshold: f64) -> usize {
+        let old: HashMap<_, _> = baseline
+            .results
+            .iter()
+            .map(|e| ((&*e.benchmark, &*e.input), e))
+            .collect();
+
+        eprintln!(
+            "\n{:>12} against the baseline (from {}):",
+            "Comparing".cyan().bold(),
+            baseline.board.bold(),
+        );
+
+        let mut regressions = 0;
+        for new in &self.results {
+            let label = format!("{} on `{}`", new.benchmark, new.input);
+            let old = if let Some(old) = old.get(&(&*new.benchmark, &*new.input)) {
+                old
+            } else {
+                eprintln!("  {label}: {}", "new".dimmed());
+                continue;
+            };
+
+            let change = if old.mean == 0.0 {
+                0.0
+            } else {
+                (new.mean - old.mean) / old.mean * 100.0
+            };
+            let numbers = format!(
+                "{:.1} -> {:.1} {} ({change:+.1}%)",
+                old.mean, new.mean, self.unit
+            );
+
+            if change > threshold {
+                regressions += 1;
+                eprintln!("  {label}: {} {}", numbers.red().bold(), "regressed".red());
+            } else if change < -threshold {
+                eprintln!("  {label}: {} {}", numbers.green(), "improved".green());
+            } else {
+                eprintln!("  {label}: {}", numbers.dimmed());
+            }
+        }
+
+        let missing = baseline.results.iter().filter(|e| {
+            !self
+                .results
+                .iter()
+                .any(|n| (&n.benchmark, &n.input) == (&e.benchmark, &e.input))
+        });
+        for e in missing {
+            eprintln!("  {} on `{}`: {}", e.benchmark, e.input, "missing".yellow());
+        }
+
+        regressions
+    }
+}
+
+/// A [`Reporter`] that keeps the min, max, and mean of each (benchmark,
+/// input) pair, and a description of each failure.
+#[derive(Debug, Default)]
+pub struct Collector {
+    single: Option<&'static str>,
+    suite: Option<&'static str>,
+    pairs: Vec<Pair>,
+    pub failures: Vec<String>,
+}
+
+#[derive(Debug)]
+struct Pair {
+    benchmark: String,
+    input: String,
+    count: u64,
+    min: u64,
+    max: u64,
+    sum: u128,
+}
+
+impl Collector {
+    fn record(&mut self, benchmark: String, input: String, m: u64) {
+        // Runs for a pair arrive together so this is usually the last one.
+        let pair = self
+            .pairs
+            .iter_mut()
+            .rev()
+            .find(|p| (&p.benchmark, &p.input) == (&benchmark, &input));
+
+        match pair {
+            Some(p) => {
+                p.count += 1;
+                p.min = p.min.min(m);
+                p.max = p.max.max(m);
+                p.sum += m as u128;
+            }
+            None => self.pairs.push(Pair {
+                benchmark,
+                input,
+                count: 1,
+                min: m,
+                max: m,
+                sum: m as u128,
+            }),
+        }
+    }
+
+    fn name(&self, benchmark: &str) -> String {
+        match self.suite {
+            Some(suite) => format!("{suite}/{benchmark}"),
+            None => benchmark.to_string(),
+        }
+    }
+
+    /// Records a JSON event if it's a measurement (see the module docs);
+    /// returns `false` for other events.
+    pub fn event(&mut self, event: &Value) -> bool {
+        let (benchmark, measurement) = match (&event["benchmark"], &event["measurement"]) {
+            (Value::String(b), Value::Number(m)) if m.is_u64() => (b, m.as_u64().unwrap()),
+            _ => return false,
+        };
+        let input = match &event["input"] {
+            Value::String(s) => s.clone(),
+            Value::Null => String::new(),
+            other => other.to_string(),
+        };
+
+        self.record(benchmark.clone(), input, measurement);
+        true
+    }
+
+    pub fn into_results(self, board: &str) -> Results {
+        let results = self
+            .pairs
+            .into_iter()
+            .map(|p| Entry {
+                benchmark: p.benchmark,
+                input: p.input,
+                iterations: p.count,
+                min: p.min,
+                max: p.max,
+                mean: p.sum as f64 / p.count as f64,
+            })
+            .collect();
+
+        Results {
+            board: board.to_string(),
+            unit: Cycles::UNIT_NAME.to_string(),
+            results,
+        }
+    }
+}
+
+impl<M: Metric> Reporter<M> for Collector {
+    type Error = Infallible;
+
+    fn starting_single_benchmark(
+        &mut self,
+        name: &'static str,
+        _inputs_size_hint: (usize, Option<usize>),
+        _iterations: usize,
+    ) {
+        self.single = Some(name);
+    }
+
+    fn single_benchmark_run(
+        &mut self,
+        _input_idx: usize,
+        input: &dyn Debug,
+        _iteration_idx: usize,
+        measurement: M::Unit,
+    ) {
+        let name = self.single.unwrap_or_default().to_string();
+        self.record(name, format!("{input:?}"), measurement.to_raw());
+    }
+
+    fn ending_single_benchmark(&mut self, _name: &'static str) {
+        self.single = None;
+    }
+
+    fn starting_new_benchmark_suite<I: Iterator<Item = (&'static str, usize)> + Clone>(
+        &mut self,
+        name: &'static str,
+        _inputs_size_hint: (usize, Option<usize>),
+        _benchmarks: I,
+        _axes: &[ubench::runner::Axis<'_>],
+    ) {
+        self.suite = Some(name);
+    }
+
+    fn suite_benchmark_run(
+        &mut self,
+        _input_idx: usize,
+        input: &dyn Debug,
+        _benchmark_idx: usize,
+        benchmark_name: &'static str,
+        _iteration_idx: usize,
+        _num_iterations: usize,
+        measurement: M::Unit,
+    ) {
+        let name = self.name(benchmark_name);
+        self.record(name, format!("{input:?}"), measurement.to_raw());
+    }
+
+    fn ending_benchmark_suite(&mut self, _name: &'static str) {
+        self.suite = None;
+    }
+
+    fn verification_failed(
+        &mut self,
+        _input_idx: usize,
+        input: &dyn Debug,
+        benchmark_name: &'static str,
+        _iteration_idx: usize,
+        message: &'static str,
+    ) {
+        let name = self.name(benchmark_name);
+        self.failures.push(format!(
+            "{name} on `{input:?}` failed verification: {message}"
+        ));
+    }
+
+    fn benchmark_panicked(
+        &mut self,
+        _input_idx: usize,
+        input: &dyn Debug,
+        benchmark_name: &'static str,
+        message: &str,
+    ) {
+        let name = self.name(benchmark_name);
+        self.failures
+            .push(format!("{name} on `{input:?}` panicked: {message}"));
+    }
+}
@@ -0,0 +1,169 @@
+//! `--test`: shows a device's test events (see `ubench::device`) the way
+//! libtest would.
+//!
+//! Output from the device is held on to while a test runs and only shown if
+//! it fails. Programs that don't send test events are treated as one test
+//! (named after the binary) that passes if the program finishes.
+
+use std::{
+    io::{self, Write},
+    time::Instant,
+};
+
+use owo_colors::OwoColorize;
+use ubench::device::{Line, Outcome, TestEvent};
+
+pub struct TestRun {
+    start: Instant,
+    seen_events: bool,
+    running: Option<String>,
+    // Output since the running test started.
+    output: Vec<String>,
+    // Output and message of a panic outside of a test; reported as the
+    // program failing in `finish`.
+    crash: Option<Vec<String>>,
+
+    passed: usize,
+    ignored: usize,
+    // (name, output and message)
+    failed: Vec<(String, String)>,
+}
+
+impl TestRun {
+    pub fn new() -> Self {
+        TestRun {
+            start: Instant::now(),
+            seen_events: false,
+            running: None,
+            output: Vec::new(),
+            crash: None,
+            passed: 0,
+            ignored: 0,
+            failed: Vec::new(),
+        }
+    }
+
+    pub fn line(&mut self, line: Line) {
+        let event = match TestEvent::from_line(&line) {
+            Some(e) => e,
+            None if self.running.is_some() => return self.output.push(line.to_string()),
+            None => return println!("{line}"),
+        };
+        self.seen_events = true;
+
+        match event {
+            TestEvent::Plan(n) => println!("\nrunning {n} test{}", plural(n)),
+            TestEvent::Started(name) => {
+                print!("test {name} ... ");
+                io::stdout().flush().unwrap();
+                self.running = Some(name);
+                self.output.clear();
+            }
+            TestEvent::Finished {
+                name,
+                outcome,
+                message,
+            } => {
+                if self.running.take().as_ref() != Some(&name) {
+                    print!("test {name} ... ");
+                }
+
+                let output = std::mem::take(&mut self.output);
+                match outcome {
+                    Outcome::Ok => self.passed(),
+                    Outcome::Ignored => self.ignored(),
+                    Outcome::Failed => self.failed(name, output, message),
+                }
+            }
+        }
+    }
+
+    /// Fails the running test, if there is one; otherwise the program itself
+    /// counts as a failed test (in `finish`).
+    pub fn panicked(&mut self, message: String) {
+        let mut output = std::mem::take(&mut self.output);
+        let message = format!("device panicked:\n{message}");
+        match self.running.take() {
+            Some(name) => self.failed(name, output, Some(message)),
+            None => {
+                output.push(message);
+                self.crash = Some(output);
+            }
+        }
+    }
+
+    /// Prints the summary; `problems` are failures outside of tests (i.e.
+    /// benchmarks that failed verification) that fail the program.
+    ///
+    /// Returns whether everything passed.
+    pub fn finish(mut self, program: &str, problems: Vec<String>) -> bool {
+        let program_failed = self.crash.is_some() || !problems.is_empty();
+        if !self.seen_events || program_failed {
+            if !self.seen_events {
+                println!("\nrunning 1 test");
+            }
+            print!("test {program} ... ");
+
+            if program_failed {
+                let mut output = self.crash.take().unwrap_or_default();
+                output.extend(problems);
+                self.failed(program.to_string(), output, None);
+            } else {
+                self.passed();
+            }
+        }
+
+        if !self.failed.is_empty() {
+            println!("\nfailures:\n");
+            for (name, output) in &self.failed {
+                println!("---- {name} stdout ----\n{}\n", output.trim_end());
+            }
+
+            println!("failures:");
+            for (name, _) in &self.failed {
+                println!("    {name}");
+            }
+        }
+
+        let ok = self.failed.is_empty();
+        let result = if ok {
+            "ok".green().to_string()
+        } else {
+            "FAILED".red().to_string()
+        };
+        println!(
+            "\ntest result: {result}. {} passed; {} failed; {} ignored; 0 measured; \
+            0 filtered out; finished in {:.2}s\n",
+            self.passed,
+            self.failed.len(),
+            self.ignored,
+            self.start.elapsed().as_secs_f64(),
+        );
+
+        ok
+    }
+
+    fn passed(&mut self) {
+        self.passed += 1;
+        println!("{}", "ok".green());
+    }
+
+    fn ignored(&mut self) {
+        self.ignored += 1;
+        println!("{}", "ignored".yellow());
+    }
+
+    fn failed(&mut self, name: String, mut output: Vec<String>, message: Option<String>) {
+        println!("{}", "FAILED".red());
+        output.extend(message);
+        self.failed.push((name, output.join("\n")));
+    }
+}
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
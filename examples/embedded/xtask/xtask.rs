@@ -1,9 +1,9 @@
 use std::{
     env::{self, consts},
     fs,
-    io::{self, BufRead, BufReader, Read, Write},
+    io::{self, Write},
     path::{Path, PathBuf},
-    process, str,
+    process,
     sync::mpsc,
     time::{Duration, Instant},
 };
@@ -12,13 +12,25 @@ use crossbeam_utils::thread;
 use downloader::{Download, Downloader};
 use faccess::{AccessMode, PathExt};
 use owo_colors::OwoColorize;
-use serialport::{
-    ClearBuffer, DataBits, FlowControl, Parity, SerialPort, SerialPortInfo, SerialPortType,
-    StopBits, UsbPortInfo,
+use serialport::{ClearBuffer, SerialPortType, UsbPortInfo};
+use ubench::{
+    device::{self, DeviceRunner, Line, RunError},
+    reporters::{BasicReporter, BinaryDecoder},
 };
 use which::which;
 use xshell::{cmd, Shell};
 
+mod board;
+use board::{Board, Flasher};
+
+mod gdb;
+
+mod report;
+use report::{Collector, Cycles, ReportOptions, Results};
+
+mod testing;
+use testing::TestRun;
+
 #[derive(Default, Debug)]
 pub enum Mode {
     #[default]
@@ -29,7 +41,10 @@ pub enum Mode {
     Debug,
 }
 
-const BAUD_RATE: u32 = 1_500_000; // TODO: support grabbing from CLI args
+const DEFAULT_BOARD_FILE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../board.toml");
+
+// `BinaryReporter` frames sent through a `FrameLines`.
+const FRAME_PREFIX: &str = "ubench-frame [";
 
 // TODO: use `structopt` or something instead...
 fn main() -> Result<(), u32> {
@@ -39,10 +54,17 @@ fn main() -> Result<(), u32> {
 
     let mut mode = Mode::default();
     let mut bin = None;
+    let mut board_file = env::var_os("UBENCH_BOARD").map(PathBuf::from);
+    let mut port = env::var("UBENCH_PORT").ok();
+    let mut baud = env::var("UBENCH_BAUD").ok();
+    let mut report = false;
+    let mut save = env::var_os("UBENCH_SAVE").map(PathBuf::from);
+    let mut baseline = env::var_os("UBENCH_BASELINE").map(PathBuf::from);
+    let mut threshold = env::var("UBENCH_THRESHOLD").ok();
 
     let mut args = env::args().skip(1);
     let mut err = false;
-    for a in &mut args {
+    while let Some(a) = args.next() {
         match &*a {
             "--bench" => {
                 mode = Mode::Bench;
@@ -59,6 +81,28 @@ fn main() -> Result<(), u32> {
             "--debug" => {
                 mode = Mode::Debug;
             }
+            "--report" => {
+                report = true;
+            }
+            opt @ ("--board" | "--port" | "--baud" | "--save" | "--baseline" | "--threshold") => {
+                let val = if let Some(v) = args.next() {
+                    v
+                } else {
+                    err = true;
+                    eprintln!("`{opt}` needs a value!");
+                    continue;
+                };
+
+                match opt {
+                    "--board" => board_file = Some(PathBuf::from(val)),
+                    "--port" => port = Some(val),
+                    "--baud" => baud = Some(val),
+                    "--save" => save = Some(PathBuf::from(val)),
+                    "--baseline" => baseline = Some(PathBuf::from(val)),
+                    "--threshold" => threshold = Some(val),
+                    _ => unreachable!(),
+                }
+            }
             "--" => {
                 break;
             }
@@ -108,35 +152,83 @@ fn main() -> Result<(), u32> {
         return Err(2);
     };
 
-    mode.run(bin)
+    // Flags and env vars take precedence over the board file:
+    let board = match &board_file {
+        Some(f) => Board::load(f, true),
+        None => Board::load(Path::new(DEFAULT_BOARD_FILE), false),
+    };
+    let mut board = board.map_err(|e| {
+        eprintln!("{}: bad board config {e}", "error".red().bold());
+        5u32
+    })?;
+    if let Some(p) = port {
+        board.serial.port = Some(p);
+    }
+    if let Some(b) = baud {
+        board.serial.baud = b.parse().map_err(|e| {
+            eprintln!("{}: bad baud rate `{b}`: {e}", "error".red().bold());
+            1u32
+        })?;
+    }
+
+    // `--save`/`--baseline`/`--threshold` imply `--report`; the baseline is
+    // read now so a bad one doesn't cost a run.
+    let report = if report || save.is_some() || baseline.is_some() || threshold.is_some() {
+        let baseline = baseline.map(|b| Results::load(&b)).transpose();
+        let baseline = baseline.map_err(|e| {
+            eprintln!("{}: bad baseline {e}", "error".red().bold());
+            5u32
+        })?;
+        let threshold = threshold.map_or(Ok(5.0), |t| t.parse()).map_err(|e| {
+            eprintln!("{}: bad threshold: {e}", "error".red().bold());
+            1u32
+        })?;
+
+        Some(ReportOptions {
+            save: save.unwrap_or_else(|| ReportOptions::default_save_path(&bin)),
+            baseline,
+            threshold,
+        })
+    } else {
+        None
+    };
+
+    mode.run(bin, &board, report)
+}
+
+/// The binary's name without the hash cargo adds to tests and benches
+/// (`bench-0123abcd...`).
+fn bin_name(bin: &Path) -> String {
+    let name = bin.file_stem().unwrap().to_string_lossy();
+    let is_hash = |h: &str| h.len() == 16 && h.bytes().all(|b| b.is_ascii_hexdigit());
+    match name.rsplit_once('-') {
+        Some((n, hash)) if is_hash(hash) => n.to_string(),
+        _ => name.into_owned(),
+    }
 }
 
-// TODO: support explicitly specifying the device! (once we have an alternative
-// to `lm4flash`)
-fn find_device() -> String {
+fn find_device(board: &Board) -> String {
     const UDEV_RULE_HELP: &str =
         "https://github.com/ut-utp/.github/wiki/Dev-Environment-Setup#udev-rule";
 
+    if let Some(port) = &board.serial.port {
+        return port.clone();
+    }
+
     let available_ports = serialport::available_ports().expect("couldn't detect available device");
     let mut found_ports_without_perms = None;
     let found_port = available_ports
         .into_iter()
-        .filter(|p| {
-            matches!(
-                p,
-                SerialPortInfo {
-                    port_type: SerialPortType::UsbPort(UsbPortInfo {
-                        vid: 0x1cbe,
-                        pid: 0x00fd,
-                        ..
-                    }),
-                    ..
-                }
-            )
+        .filter(|p| match (&p.port_type, board.serial.usb) {
+            (SerialPortType::UsbPort(UsbPortInfo { vid, pid, .. }), Some(usb)) => {
+                (*vid, *pid) == (usb.vid, usb.pid)
+            }
+            (SerialPortType::UsbPort(_), None) => true,
+            _ => false,
         })
         .filter(|p| {
             // On Linux (and technically also macOS but not in practice) users
-            // may not have permissions to access the TM4C's serial port.
+            // may not have permissions to access the board's serial port.
             //
             // Additionally, users may have a `udev` rule that creates
             // _symlinks_ with permissions that permit access but that do not
@@ -157,13 +249,15 @@ fn find_device() -> String {
         .next()
         .unwrap_or_else(|| {
             eprintln!(
-                "{}: couldn't find a USB Serial device that looks like a TM4C...\n",
-                "error".red().bold()
+                "{}: couldn't find a USB Serial device that looks like a {}...\n",
+                "error".red().bold(),
+                board.name,
             );
 
             if let Some(ex) = found_ports_without_perms {
                 eprintln!(
-                    "We found device paths that look like TM4Cs (i.e. `{}`) that you do not have permissions to access.\n",
+                    "We found device paths that look like {}s (i.e. `{}`) that you do not have permissions to access.\n",
+                    board.name,
                     ex.bold(),
                 );
                 if consts::OS != "macos" && consts::OS != "ios" {
@@ -182,10 +276,12 @@ fn find_device() -> String {
                     )
                 }
             } else {
-                eprintln!(
-                    "{}\n",
-                    "Is your board plugged in (top port) and powered on?".yellow()
-                );
+                let hint = if let Flasher::Lm4flash = board.flasher {
+                    "Is your board plugged in (top port) and powered on?"
+                } else {
+                    "Is your board plugged in and powered on? (or pass `--port`)"
+                };
+                eprintln!("{}\n", hint.yellow());
             }
 
             std::process::exit(6)
@@ -337,10 +433,8 @@ fn find_or_get_lm4flash(sh: &Shell) -> PathBuf {
     bin_path
 }
 
-// todo: try to get the _serial_ to specify to `lm4flash` which
-// board to flash..
-fn flash_program(sh: &Shell, elf_binary: &Path, _device_port: &str) {
-    let lm4flash = find_or_get_lm4flash(sh);
+// Makes a raw binary (for flashers that don't take ELFs) next to `elf_binary`.
+fn make_raw_binary(sh: &Shell, elf_binary: &Path) -> PathBuf {
     let objcopy = find_llvm_objcopy(sh);
     let axf_bin_path = {
         let p = elf_binary.parent().unwrap();
@@ -352,33 +446,99 @@ fn flash_program(sh: &Shell, elf_binary: &Path, _device_port: &str) {
         .quiet()
         .run()
         .unwrap();
-    let mut cmd: process::Command = cmd!(sh, "{lm4flash} -E -v {axf_bin_path}")
-        .quiet()
-        .ignore_stdout()
-        .into();
-    let res = cmd.output().unwrap();
-
-    if !res.status.success() {
-        const ICDI_INSTRUCTIONS_LINK: &str = "https://www.ti.com/lit/ml/spmu287c/spmu287c.pdf";
-        const ICDI_INSTALLATION_LINK: &str = "https://www.ti.com/litv/zip/spmc016a";
+    axf_bin_path
+}
 
-        let err = str::from_utf8(&res.stderr).unwrap();
+fn find_tool(name: &str) -> PathBuf {
+    which(name).unwrap_or_else(|_| {
         eprintln!(
-            "{} ({}):\n{err}\n",
-            "\nError when flashing".red().bold(),
-            res.status.bold()
+            "{}: couldn't find `{}` on $PATH; it's needed to flash this board.",
+            "\nerror".red().bold(),
+            name.bold(),
         );
+        std::process::exit(4)
+    })
+}
 
-        if cfg!(windows) && err.contains("Unable to find any ICDI devices") {
-            eprintln!(
-                "{}\n\n\tDownload link: {}\n\tInstructions:  {}\n",
-                "Have you installed the TI ICDI drivers?".yellow(),
-                ICDI_INSTALLATION_LINK.underline(),
-                ICDI_INSTRUCTIONS_LINK.underline(),
+// todo: try to get the _serial_ to specify to `lm4flash` which
+// board to flash..
+fn flash_program(sh: &Shell, elf_binary: &Path, board: &Board) {
+    let cmds: Vec<xshell::Cmd<'_>> = match &board.flasher {
+        Flasher::Lm4flash => {
+            let lm4flash = find_or_get_lm4flash(sh);
+            let axf_bin_path = make_raw_binary(sh, elf_binary);
+            vec![cmd!(sh, "{lm4flash} -E -v {axf_bin_path}")]
+        }
+        Flasher::ProbeRs { chip, probe } => {
+            let probe_rs = find_tool("probe-rs");
+            let probe: Vec<_> = probe.iter().flat_map(|p| ["--probe", p]).collect();
+            vec![
+                cmd!(sh, "{probe_rs} download --chip {chip}")
+                    .args(&probe)
+                    .arg(elf_binary),
+                cmd!(sh, "{probe_rs} reset --chip {chip}").args(&probe),
+            ]
+        }
+        Flasher::Openocd { files } => {
+            let openocd = find_tool("openocd");
+            let files: Vec<_> = files.iter().flat_map(|f| ["-f", f]).collect();
+            // OpenOCD's Tcl wants forward slashes, even on Windows.
+            let program = format!(
+                "program {} verify reset exit",
+                elf_binary.display().to_string().replace('\\', "/")
             );
+            vec![cmd!(sh, "{openocd} {files...} -c {program}")]
         }
+        Flasher::Custom { command } => {
+            let (prog, args) = if let Some(c) = command.split_first() {
+                c
+            } else {
+                eprintln!(
+                    "{}: the custom flasher's `command` is empty",
+                    "\nerror".red().bold()
+                );
+                std::process::exit(5)
+            };
+
+            let bin = if command.iter().any(|a| a.contains("{bin}")) {
+                make_raw_binary(sh, elf_binary)
+            } else {
+                PathBuf::new()
+            };
+            let args = args.iter().map(|a| {
+                a.replace("{elf}", &elf_binary.display().to_string())
+                    .replace("{bin}", &bin.display().to_string())
+            });
+            vec![cmd!(sh, "{prog} {args...}")]
+        }
+    };
 
-        std::process::exit(4);
+    for cmd in cmds {
+        let mut cmd: process::Command = cmd.quiet().ignore_stdout().into();
+        let res = cmd.output().unwrap();
+
+        if !res.status.success() {
+            const ICDI_INSTRUCTIONS_LINK: &str = "https://www.ti.com/lit/ml/spmu287c/spmu287c.pdf";
+            const ICDI_INSTALLATION_LINK: &str = "https://www.ti.com/litv/zip/spmc016a";
+
+            let err = String::from_utf8_lossy(&res.stderr);
+            eprintln!(
+                "{} ({}):\n{err}\n",
+                "\nError when flashing".red().bold(),
+                res.status.bold()
+            );
+
+            if cfg!(windows) && err.contains("Unable to find any ICDI devices") {
+                eprintln!(
+                    "{}\n\n\tDownload link: {}\n\tInstructions:  {}\n",
+                    "Have you installed the TI ICDI drivers?".yellow(),
+                    ICDI_INSTALLATION_LINK.underline(),
+                    ICDI_INSTRUCTIONS_LINK.underline(),
+                );
+            }
+
+            std::process::exit(4);
+        }
     }
 }
 
@@ -401,9 +561,16 @@ impl Mode {
         );
     }
 
-    fn run(&self, bin: PathBuf) -> Result<(), u32> {
-        let dev_path = find_device();
+    fn run(&self, bin: PathBuf, board: &Board, report: Option<ReportOptions>) -> Result<(), u32> {
+        // GDB does the flashing (if any) and there's no serial port to watch.
+        if let Mode::Debug = self {
+            self.print(&bin, &board.name);
+            return gdb::debug(&bin, board);
+        }
+
+        let dev_path = find_device(board);
         self.print(&bin, &dev_path);
+        let name = bin_name(&bin);
 
         let sh = Shell::new().unwrap();
 
@@ -412,45 +579,28 @@ impl Mode {
 
         // This is a bit tricky. We want to only see output from the current
         // execution of the program so we want to only start reading from the
-        // serial port _after_ `lm4flash` has started.
+        // serial port _after_ the flasher has started.
         //
         // But we also want to clear the OS's buffer so we don't get old output.
-        // We can't do this _after_ `lm4flash` has run because then we'll lose
-        // some of the new output and we can't do this _before_ `lm4flash` runs
-        // because then we might get some of the old output from between when
-        // `lm4flash` runs and when we clear the buffer.
-        //
-        // So, we clear the buffer _while_ `lm4flash` is running.
-        let mut dev = serialport::new(&dev_path, BAUD_RATE)
-            .data_bits(DataBits::Eight)
-            .flow_control(FlowControl::None)
-            .parity(Parity::None)
-            .stop_bits(StopBits::One)
-            .timeout(timeout)
-            .open_native()
-            .unwrap();
-
-        // On Windows, the timeout also governs how long the OS will hold onto
-        // data in its buffers before giving it to us when:
-        //   - we are only _receiving_ data and are not sending anything
-        //   - the data in the OS' buffers does not exceed its buffer size
+        // We can't do this _after_ the flasher has run because then we'll lose
+        // some of the new output and we can't do this _before_ the flasher
+        // runs because then we might get some of the old output from between
+        // when the flasher runs and when we clear the buffer.
         //
-        // This is suboptimal for our use case (just receiving data) so: on
-        // Windows we set the timeout to be very low *and* make it so that
-        // receiving a `io::ErrorKind::TimedOut` is not an error.
+        // So, we clear the buffer _while_ the flasher is running.
         //
-        // We actually still _do_ want a timeout (for use in CI and such); on
-        // Windows we reconstruct an approximation of this.
-        #[cfg(windows)]
-        let (mut current_timeout_count, timeouts_before_error) = {
-            let short_timeout = Duration::from_millis(50);
-            dev.set_timeout(short_timeout).unwrap();
-            let timeouts_before_error = timeout.as_nanos() / short_timeout.as_nanos();
-
-            (0, timeouts_before_error)
+        // (The port has a short read timeout; on Windows that also governs how
+        // long the OS holds onto data before giving it to us. `DeviceRunner`
+        // takes care of the actual timeout.)
+        let mut dev = match device::open_serial(&dev_path, board.serial.baud) {
+            Ok(dev) => dev,
+            Err(e) => {
+                eprintln!("{}: couldn't open `{dev_path}`: {e}", "error".red().bold());
+                return Err(6);
+            }
         };
 
-        // Start up `lm4flash`:
+        // Start up the flasher:
         thread::scope(|s| {
             let (tx, rx) = mpsc::channel();
             s.spawn(move |_| {
@@ -476,7 +626,7 @@ impl Mode {
             });
 
             s.spawn(move |_| {
-                flash_program(&sh, &bin, &dev_path);
+                flash_program(&sh, &bin, board);
                 tx.send(()).unwrap();
             });
 
@@ -491,11 +641,6 @@ impl Mode {
         if let Mode::Flash = self {
             return Ok(());
         }
-        if let Mode::Debug = self {
-            // exec into gdb, etc.
-            // (skip flashing too!)
-            todo!()
-        }
 
         if let Mode::Run = self {
             // Show serial output.
@@ -515,154 +660,152 @@ impl Mode {
             let mut out = io::stdout();
             loop {
                 match io::copy(&mut dev, &mut out) {
-                    Ok(_) => {
-                        #[cfg(windows)]
-                        {
-                            current_timeout_count = 0;
-                        }
-                    }
-                    // If we're on windows, omit timeout errors, maybe:
-                    #[cfg(windows)]
-                    Err(err)
-                        if current_timeout_count < timeouts_before_error
-                            && err.kind() == io::ErrorKind::TimedOut =>
-                    {
-                        current_timeout_count += 1;
-                    }
-                    Err(err) => {
-                        #[cfg(windows)]
-                        {
-                            current_timeout_count = 0;
-                        }
-                        eprintln!("error: {err:?}")
-                    }
+                    Ok(_) => {}
+                    Err(err) if err.kind() == io::ErrorKind::TimedOut => {}
+                    Err(err) => eprintln!("error: {err:?}"),
                 }
             }
         }
 
-        enum Choice {
-            SendToOutput,
-            OmitFromOutput,
-            Break,
-        }
-        fn process<E>(
-            inp: &mut impl Read,
-            sink: &mut impl Write,
-            mut line_func: impl FnMut(&str) -> Result<Choice, E>,
-            #[cfg(windows)] timeouts_before_error: u128,
-        ) -> Result<(), E> {
-            use Choice::*;
-
-            #[cfg(windows)]
-            let mut timeout_count = 0;
-
-            let mut inp = BufReader::new(inp);
-            let mut buf = String::new();
-            loop {
-                #[cfg(not(windows))]
-                inp.read_line(&mut buf).unwrap();
-
-                #[cfg(windows)]
-                {
-                    if let Err(e) = inp.read_line(&mut buf) {
-                        if e.kind() == io::ErrorKind::TimedOut {
-                            timeout_count += 1;
-                            if timeout_count < timeouts_before_error {
-                                continue;
-                            }
-                        }
-
-                        panic!("I/O error: {e}");
-                    } else {
-                        timeout_count = 0;
-                    }
+        fn crash(a: Result<(), RunError>) -> Result<(), u32> {
+            match a {
+                Ok(()) => Ok(()),
+                Err(RunError::Panicked(m)) => {
+                    eprintln!(
+                        "{}:\n{}\n{}\n",
+                        "error".red().bold(),
+                        "Embedded device panicked! Got:".dimmed(),
+                        m.bold()
+                    );
+                    Err(3)
                 }
-
-                match line_func(&buf)? {
-                    SendToOutput => sink.write_all(buf.as_bytes()).unwrap(),
-                    Break => break,
-                    OmitFromOutput => {}
+                Err(e) => {
+                    eprintln!("{}: {e}", "error".red().bold());
+                    Err(3)
                 }
-
-                buf.clear();
             }
-
-            Ok(())
         }
 
-        let mut err_buf = String::new();
-        let mut panicked = false;
-        let watch_for_panics_and_ends = move |line: &str| -> Result<Choice, String> {
-            const PANIC_DELIM: &str = "++++++++++";
-            const END_DELIM: &str = "==========";
-            let s = line.trim_end();
-            if panicked {
-                if s == PANIC_DELIM {
-                    // TODO: return a better error type, etc.
-                    return Err(format!(
-                        "{}\n{}\n",
-                        "Embedded device panicked! Got:".dimmed(),
-                        err_buf.bold()
-                    ));
-                }
+        let runner = DeviceRunner::new().timeout(timeout);
 
-                err_buf.push_str(line);
-                return Ok(Choice::OmitFromOutput);
-            }
+        match self {
+            Mode::Bench => {
+                // Show the report as it arrives, decoding it if it's sent as
+                // frames, and collect the results for post processing.
+                let mut stdout = io::stdout();
+                let mut basic = BasicReporter::new_with_stdout(&mut stdout);
+                let mut collector = Collector::default();
+                let mut decoder = BinaryDecoder::new();
+                let mut decode_err = None;
+
+                let res = runner.stream(&mut dev, |line| match line {
+                    Line::Text(t) if t.contains(FRAME_PREFIX) => {
+                        let r = &mut (&mut basic, &mut collector);
+                        if let Err(e) = decoder.feed_frame_line::<Cycles, _>(&t, r) {
+                            decode_err.get_or_insert(e);
+                        }
+                    }
+                    Line::Event(e) if collector.event(&e) => {}
+                    line => println!("{line}"),
+                });
+                crash(res)?;
 
-            if s == PANIC_DELIM {
-                panicked = true;
-                Ok(Choice::OmitFromOutput)
-            } else if s == END_DELIM {
-                Ok(Choice::Break)
-            } else {
-                Ok(Choice::SendToOutput)
-            }
-        };
+                if let Some(e) = decode_err {
+                    eprintln!(
+                        "{}: some of the report couldn't be decoded ({e}); \
+                        the results may be incomplete",
+                        "warning".yellow().bold(),
+                    );
+                }
 
-        fn crash(a: Result<(), String>) -> Result<(), u32> {
-            match a {
-                Ok(()) => Ok(()),
-                Err(m) => {
-                    eprintln!("{}:\n{m}", "error".red().bold());
-                    Err(3)
+                match report {
+                    Some(opts) => post_process(collector, board, opts),
+                    None => check_failures(&collector.failures),
                 }
             }
-        }
+            Mode::Test => {
+                // Benchmarks run as tests still send frames; these aren't
+                // shown but benchmarks that fail verification (or panic) fail
+                // the run.
+                let mut tests = TestRun::new();
+                let mut collector = Collector::default();
+                let mut decoder = BinaryDecoder::new();
+
+                let res = runner.stream(&mut dev, |line| match line {
+                    Line::Text(t) if t.contains(FRAME_PREFIX) => {
+                        let _ = decoder.feed_frame_line::<Cycles, _>(&t, &mut collector);
+                    }
+                    line => tests.line(line),
+                });
+                match res {
+                    Ok(()) => {}
+                    Err(RunError::Panicked(m)) => tests.panicked(m),
+                    Err(e) => return crash(Err(e)),
+                }
 
-        match self {
-            Mode::Bench => {
-                // Attach a console but be looking to grab the
-                // benchmarking output for post processing if the
-                // flags say to do so.
-                if false {
-                    // post processing mode:
-                    todo!()
+                if tests.finish(&name, collector.failures) {
+                    Ok(())
                 } else {
-                    // TODO: replace this with the JSON thing once we
-                    // get to doing that.
-                    crash(process(
-                        &mut dev,
-                        &mut io::stdout(),
-                        watch_for_panics_and_ends,
-                        #[cfg(windows)]
-                        timeouts_before_error,
-                    ))
+                    // Like libtest.
+                    Err(101)
                 }
             }
-            Mode::Test => {
-                // Like `Bench` but different post processing.
-                crash(process(
-                    &mut dev,
-                    &mut io::stdout(),
-                    watch_for_panics_and_ends,
-                    #[cfg(windows)]
-                    timeouts_before_error,
-                ))
-            }
             Mode::Run => unreachable!(),
             Mode::Debug => unreachable!(),
             Mode::Flash => unreachable!(),
         }
     }
 }
+
+// Benchmarks that failed verification (or panicked) fail the run.
+fn check_failures(failures: &[String]) -> Result<(), u32> {
+    for f in failures {
+        eprintln!("{}: {f}", "error".red().bold());
+    }
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(3)
+    }
+}
+
+fn post_process(mut collector: Collector, board: &Board, opts: ReportOptions) -> Result<(), u32> {
+    let failures = std::mem::take(&mut collector.failures);
+    let results = collector.into_results(&board.name);
+    if results.results.is_empty() {
+        eprintln!(
+            "{}: didn't get any results from the device; it should send \
+            `BinaryReporter` frames through a `FrameLines` (or JSON events)",
+            "error".red().bold(),
+        );
+        return Err(3);
+    }
+
+    if let Err(e) = results.save(&opts.save) {
+        eprintln!("{}: {e}", "error".red().bold());
+        return Err(5);
+    }
+    eprintln!(
+        "\n{:>12} {} results to {}",
+        "Saved".green().bold(),
+        results.results.len(),
+        opts.save.display().bold(),
+    );
+
+    let regressions = match &opts.baseline {
+        Some(baseline) => results.compare(baseline, opts.threshold),
+        None => 0,
+    };
+
+    check_failures(&failures)?;
+    if regressions != 0 {
+        eprintln!(
+            "\n{}: {regressions} benchmark(s) regressed by more than {}%",
+            "error".red().bold(),
+            opts.threshold,
+        );
+        return Err(7);
+    }
+
+    Ok(())
+}
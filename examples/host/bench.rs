@@ -2,9 +2,14 @@ use ubench::{metrics::*, reporters::*, *};
 use ubench_host_example::*;
 
 fn main() {
-    let mut out = std::io::stderr();
+    let (mut out, mut status) = (std::io::stderr(), std::io::stderr());
     let mut m = StdSysTime;
-    let mut r = BasicReporter::new_with_io_write(&mut out);
+    let mut r = ProgressReporter::new_with_io_write(
+        BasicReporter::new_with_stderr(&mut out),
+        &mut status,
+        // A status line on terminals, the odd line otherwise (i.e. in logs):
+        ProgressStyle::detect(Stream::Stderr, 100),
+    );
 
     BenchmarkRunner::new()
         .set_iterations(20)
@@ -15,5 +20,6 @@ fn main() {
                 .add("iterative", Iterative)
                 .add("closed form", ClosedForm),
         )
-        .run(&mut m, &mut r);
+        .run(&mut m, &mut r)
+        .unwrap();
 }
@@ -0,0 +1,9 @@
+use std::{env, fs, path::PathBuf};
+
+// Puts `memory.x` where `cortex-m-rt`'s `link.x` can find it.
+fn main() {
+    let out = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    fs::copy("memory.x", out.join("memory.x")).unwrap();
+    println!("cargo:rustc-link-search={}", out.display());
+    println!("cargo:rerun-if-changed=memory.x");
+}
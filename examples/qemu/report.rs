@@ -0,0 +1,155 @@
+//! Runs a few benchmarks on the emulated board, reporting over semihosting,
+//! and checks that the report came out right; the exit code tells `cargo test`
+//! how it went.
+//!
+//! QEMU doesn't emulate the DWT's cycle counter (it reads as 0) so this checks
+//! the report's shape, not the measurements.
+
+#![no_std]
+#![no_main]
+
+#[cfg(not(target_arch = "arm"))]
+compile_error!("please run with the target in `.cargo/config` (`thumbv7m-none-eabi`)!");
+
+use core::fmt::{self, Write};
+use core::panic::PanicInfo;
+
+use cortex_m::asm::semihosting_syscall;
+use cortex_m_rt::entry;
+
+use ubench::{metrics::*, reporters::*, *};
+use ubench_qemu_example::*;
+
+// See "Semihosting for AArch32 and AArch64" from ARM.
+const SYS_WRITEC: u32 = 0x03;
+const SYS_EXIT: u32 = 0x18;
+// QEMU exits with 0 for this reason and with 1 for everything else.
+const ADP_STOPPED_APPLICATION_EXIT: u32 = 0x20026;
+const ADP_STOPPED_RUN_TIME_ERROR_UNKNOWN: u32 = 0x20023;
+
+fn exit(success: bool) -> ! {
+    let reason = if success {
+        ADP_STOPPED_APPLICATION_EXIT
+    } else {
+        ADP_STOPPED_RUN_TIME_ERROR_UNKNOWN
+    };
+    // Safety: `SYS_EXIT` takes the reason directly on 32-bit targets.
+    unsafe { semihosting_syscall(SYS_EXIT, reason) };
+
+    // Not reached under QEMU.
+    loop {
+        cortex_m::asm::wfi();
+    }
+}
+
+// The debugger's console, a character at a time; for messages from the test
+// itself.
+struct Console;
+
+impl Write for Console {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.bytes() {
+            // Safety: `SYS_WRITEC` takes a pointer to the character.
+            unsafe { semihosting_syscall(SYS_WRITEC, &c as *const u8 as usize as u32) };
+        }
+        Ok(())
+    }
+}
+
+// Keeps a copy of the report to check.
+struct Capture {
+    buf: [u8; 4096],
+    len: usize,
+}
+
+impl Capture {
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.buf[..self.len]).unwrap()
+    }
+}
+
+impl Write for Capture {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let rest = &mut self.buf[self.len..];
+        if s.len() > rest.len() {
+            return Err(fmt::Error);
+        }
+
+        rest[..s.len()].copy_from_slice(s.as_bytes());
+        self.len += s.len();
+        Ok(())
+    }
+}
+
+const EXPECTED: &[&str] = &[
+    "| | fibonacci comparison |",
+    "Inputs (10 iterations each, measuring cycles)",
+    " 1. `0`",
+    " 4. `15`",
+    "    recursive: ",
+    "    iterative: ",
+    "    closed form: ",
+    "| | iterative |",
+    " 2. `30`",
+];
+
+fn options() -> FormatOptions {
+    FormatOptions {
+        line_ending: "\n",
+        ..FormatOptions::plain()
+    }
+}
+
+#[entry]
+fn main() -> ! {
+    let mut core_p = cortex_m::Peripherals::take().unwrap();
+    let mut m = CortexMCycleCount::new(&mut core_p.DWT, &mut core_p.DCB).unwrap();
+
+    let mut out = SemihostingOutput::stdout().unwrap();
+    let mut capture = Capture {
+        buf: [0; 4096],
+        len: 0,
+    };
+    let mut r = (
+        BasicReporter::new(&mut out).set_format_options(options()),
+        BasicReporter::new_with_fmt_write(&mut capture).set_format_options(options()),
+    );
+
+    let res = BenchmarkRunner::new()
+        .set_iterations(10)
+        .add(single("iterative", Iterative, [20, 30]))
+        .add(
+            suite("fibonacci comparison", (0..20).step_by(5))
+                .add("recursive", Recursive)
+                .add("iterative", Iterative)
+                .add("closed form", ClosedForm),
+        )
+        .run(&mut m, &mut r);
+
+    let mut console = Console;
+    if let Err(err) = res {
+        writeln!(console, "\nreporting failed: {}", err).unwrap();
+        exit(false);
+    }
+
+    let report = capture.as_str();
+    let mut ok = true;
+    for expected in EXPECTED {
+        if !report.contains(expected) {
+            writeln!(console, "\nmissing from the report: {:?}", expected).unwrap();
+            ok = false;
+        }
+    }
+    if report.contains("failed verification") || report.contains("panicked") {
+        writeln!(console, "\nthe report has failures").unwrap();
+        ok = false;
+    }
+
+    exit(ok)
+}
+
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    writeln!(Console, "\n{}", info).ok();
+    exit(false)
+}
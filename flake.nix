@@ -46,6 +46,7 @@
             openocd
             picocom
             lm4tools
+            qemu       # for `examples/qemu`
 
             pkg-config # host serial stuff needs this
             openssl    # xtask needs this
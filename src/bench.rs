@@ -4,6 +4,11 @@ use core::{any::Any, fmt::Debug};
 pub trait Benchmark<Inp: Any + Debug> {
     type Res;
 
+    /// Whether several runs can share one call to `setup` when batching (see
+    /// [`BatchSize`](crate::BatchSize)); benchmarks that need a fresh `setup`
+    /// for every run set this to `false` and are never batched.
+    const BATCHABLE: bool = true;
+
     /// Called before every call to `run`.
     ///
     /// For stuff you wish to have run once, use a constructor function.
@@ -13,6 +18,16 @@ pub trait Benchmark<Inp: Any + Debug> {
     /// This is what is actually measured.
     fn run(&mut self, inp: &Inp) -> Self::Res;
 
+    /// Called after every call to `run` (and before `teardown`) to check
+    /// that `res` is correct; not measured.
+    ///
+    /// Failures are handed to the [`Reporter`](crate::Reporter) (see
+    /// [`Reporter::verification_failed`](crate::Reporter::verification_failed))
+    /// and the rest of the benchmarks keep running.
+    fn verify(&mut self, inp: &Inp, res: &Self::Res) -> Result<(), &'static str> {
+        Ok(())
+    }
+
     /// Called after every call to `run`.
     ///
     /// For stuff you wish to have run once, use `Drop`.
@@ -26,3 +41,102 @@ impl<I: Any + Debug, R, F: FnMut(&I) -> R> Benchmark<I> for F {
         self(inp)
     }
 }
+
+/// A benchmark with an (unmeasured) step that prepares owned data for each
+/// run, for benchmarks that consume or mutate their input (i.e. sorting a
+/// buffer in place).
+///
+/// Wrap implementors with [`prepared`] to use them as a [`Benchmark`].
+///
+/// `prepare` is called before every call to `run` so each run starts from
+/// freshly prepared data; because of this, prepared benchmarks are never
+/// batched (see [`BatchSize`](crate::BatchSize)).
+#[allow(unused_variables)]
+pub trait PreparedBenchmark<Inp: Any + Debug> {
+    type Prepared;
+    type Res;
+
+    /// Called before every call to `run`; not measured.
+    fn prepare(&mut self, inp: &Inp) -> Self::Prepared;
+
+    /// This is what is actually measured.
+    fn run(&mut self, inp: &Inp, prepared: &mut Self::Prepared) -> Self::Res;
+
+    /// Like [`Benchmark::verify`]; also gets the data `run` was given.
+    fn verify(
+        &mut self,
+        inp: &Inp,
+        prepared: &Self::Prepared,
+        res: &Self::Res,
+    ) -> Result<(), &'static str> {
+        Ok(())
+    }
+
+    /// Called after every call to `run`, with the data from `prepare`.
+    fn teardown(&mut self, inp: &Inp, prepared: Self::Prepared, res: Self::Res) {}
+}
+
+/// A `(prepare, run)` pair of closures.
+impl<I, P, R, Prep, Run> PreparedBenchmark<I> for (Prep, Run)
+where
+    I: Any + Debug,
+    Prep: FnMut(&I) -> P,
+    Run: FnMut(&I, &mut P) -> R,
+{
+    type Prepared = P;
+    type Res = R;
+
+    fn prepare(&mut self, inp: &I) -> P {
+        (self.0)(inp)
+    }
+
+    fn run(&mut self, inp: &I, prepared: &mut P) -> R {
+        (self.1)(inp, prepared)
+    }
+}
+
+/// Adapts a [`PreparedBenchmark`] into a [`Benchmark`].
+pub fn prepared<Inp: Any + Debug, B: PreparedBenchmark<Inp>>(benchmark: B) -> Prepared<B, Inp> {
+    Prepared {
+        benchmark,
+        prepared: None,
+    }
+}
+
+/// See [`prepared`].
+pub struct Prepared<B: PreparedBenchmark<Inp>, Inp: Any + Debug> {
+    benchmark: B,
+    prepared: Option<B::Prepared>,
+}
+
+impl<B: PreparedBenchmark<Inp>, Inp: Any + Debug> Benchmark<Inp> for Prepared<B, Inp> {
+    type Res = B::Res;
+
+    // Runs in a batch would share (and mutate) the same prepared data.
+    const BATCHABLE: bool = false;
+
+    fn setup(&mut self, inp: &Inp) {
+        self.prepared = Some(self.benchmark.prepare(inp));
+    }
+
+    fn run(&mut self, inp: &Inp) -> B::Res {
+        let prepared = self
+            .prepared
+            .as_mut()
+            .expect("`setup` is called before `run`");
+        self.benchmark.run(inp, prepared)
+    }
+
+    fn verify(&mut self, inp: &Inp, res: &B::Res) -> Result<(), &'static str> {
+        match self.prepared {
+            Some(ref prepared) => self.benchmark.verify(inp, prepared, res),
+            None => Ok(()),
+        }
+    }
+
+    fn teardown(&mut self, inp: &Inp, res: B::Res) {
+        if let Some(prepared) = self.prepared.take() {
+            self.benchmark.teardown(inp, prepared, res)
+        }
+    }
+}
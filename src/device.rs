@@ -0,0 +1,314 @@
+//! Host side helpers for running benchmarks on a device and collecting what
+//! it prints (`device` feature).
+//!
+//! The device is expected to print its report a line at a time (over a UART,
+//! or anything else that shows up as an [`io::Read`] on the host) and to mark
+//! where things stop with delimiter lines:
+//!
+//!   - [`END_DELIM`] once it's done
+//!   - [`PANIC_DELIM`] before and after the panic message, if it panics
+//!
+//! ```rust,ignore
+//! // On the device, i.e. with `panic-write`:
+//! let mut uart = PanicHandler::new_with_hook(uart, |w, info| {
+//!     writeln!(w, "\n{}\n{}\n{}", PANIC_DELIM, info, PANIC_DELIM).unwrap();
+//! });
+//! BenchmarkRunner::new() /* ... */ .run(&mut metric, &mut reporter)?;
+//! writeln!(uart, "\n{}", END_DELIM).unwrap();
+//! ```
+//!
+//! Lines that are JSON objects are handed back as [`Line::Event`]s so devices
+//! can send structured output alongside text.
+//!
+//! ## Tests
+//!
+//! Devices running tests report them with these events (see [`TestEvent`]):
+//!
+//! ```json
+//! { "tests": 3 }
+//! { "test": "adds" }
+//! { "test": "adds", "result": "ok" }
+//! { "test": "subtracts" }
+//! { "test": "subtracts", "result": "failed", "message": "1 - 1 != 2" }
+//! { "test": "divides", "result": "ignored" }
+//! ```
+//!
+//! The plan (`tests`) and the start events are optional; without the latter
+//! a panic can't be attributed to a test though.
+
+use std::{
+    fmt::{self, Display},
+    io::{self, BufRead, BufReader, Read},
+    string::String,
+    thread,
+    time::{Duration, Instant},
+    vec::Vec,
+};
+
+pub use serde_json::Value;
+
+/// Printed (on its own line) by the device before and after its panic
+/// message.
+pub const PANIC_DELIM: &str = "++++++++++";
+/// Printed (on its own line) by the device once it's done.
+pub const END_DELIM: &str = "==========";
+
+/// A line of output from the device (without the line ending).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Line {
+    Text(String),
+    /// A line that parsed as a JSON object.
+    Event(Value),
+}
+
+impl Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Line::Text(t) => f.write_str(t),
+            Line::Event(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RunError {
+    /// The device panicked; this is what it printed in between the
+    /// [`PANIC_DELIM`]s (or up until the output stopped).
+    Panicked(String),
+    /// Nothing arrived for the [timeout](DeviceRunner::timeout).
+    TimedOut,
+    /// The output ended before the [`END_DELIM`].
+    Disconnected,
+    Io(io::Error),
+}
+
+impl Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::Panicked(msg) => write!(f, "the device panicked:\n{}", msg),
+            RunError::TimedOut => write!(f, "timed out waiting for the device"),
+            RunError::Disconnected => write!(f, "the device's output ended early"),
+            RunError::Io(e) => write!(f, "couldn't read from the device: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RunError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RunError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for RunError {
+    fn from(e: io::Error) -> Self {
+        RunError::Io(e)
+    }
+}
+
+/// Reads a device's output until it's done.
+///
+/// ```rust,ignore
+/// let port = ubench::device::open_serial("/dev/ttyACM0", 115_200)?;
+/// DeviceRunner::new()
+///     .timeout(Duration::from_secs(60))
+///     .stream(port, |line| println!("{}", line))?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct DeviceRunner {
+    timeout: Option<Duration>,
+    panic_delim: String,
+    end_delim: String,
+}
+
+impl Default for DeviceRunner {
+    fn default() -> Self {
+        DeviceRunner {
+            timeout: None,
+            panic_delim: PANIC_DELIM.into(),
+            end_delim: END_DELIM.into(),
+        }
+    }
+}
+
+impl DeviceRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gives up when nothing has arrived for `timeout` (no timeout by
+    /// default).
+    ///
+    /// This is checked whenever a read times out (returns
+    /// [`io::ErrorKind::TimedOut`] or [`io::ErrorKind::WouldBlock`]) so the
+    /// reader should have a timeout shorter than this; i.e. a serial port
+    /// opened with [`open_serial`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Uses other delimiters than [`PANIC_DELIM`] and [`END_DELIM`].
+    pub fn delimiters(mut self, panic: &str, end: &str) -> Self {
+        self.panic_delim = panic.into();
+        self.end_delim = end.into();
+        self
+    }
+
+    /// Calls `on_line` with each line of output until the end delimiter.
+    ///
+    /// Returns an error if the device panics, times out, or stops before
+    /// the end delimiter; lines that are part of the panic message aren't
+    /// passed to `on_line`.
+    pub fn stream<R: Read>(
+        &self,
+        device: R,
+        mut on_line: impl FnMut(Line),
+    ) -> Result<(), RunError> {
+        let mut device = BufReader::new(device);
+        let mut buf = Vec::new();
+        let mut panic_msg: Option<String> = None;
+        let mut last_read = Instant::now();
+
+        loop {
+            // Partial lines stay in `buf` across timeouts.
+            let eof = match device.read_until(b'\n', &mut buf) {
+                Ok(0) => true,
+                Ok(_) => {
+                    last_read = Instant::now();
+                    !buf.ends_with(b"\n")
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e)
+                    if e.kind() == io::ErrorKind::TimedOut
+                        || e.kind() == io::ErrorKind::WouldBlock =>
+                {
+                    if self.timeout.map_or(false, |t| last_read.elapsed() >= t) {
+                        return Err(panic_msg.map_or(RunError::TimedOut, RunError::Panicked));
+                    }
+                    if e.kind() == io::ErrorKind::WouldBlock {
+                        thread::sleep(Duration::from_millis(1));
+                    }
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            if !buf.is_empty() {
+                let line = String::from_utf8_lossy(&buf);
+                let line = line.trim_end_matches(&['\r', '\n'][..]);
+
+                if let Some(msg) = &mut panic_msg {
+                    if line.trim_end() == self.panic_delim {
+                        return Err(RunError::Panicked(panic_msg.unwrap()));
+                    }
+                    msg.push_str(line);
+                    msg.push('\n');
+                } else if line.trim_end() == self.panic_delim {
+                    panic_msg = Some(String::new());
+                } else if line.trim_end() == self.end_delim {
+                    return Ok(());
+                } else {
+                    on_line(parse(line));
+                }
+                buf.clear();
+            }
+
+            if eof {
+                return Err(panic_msg.map_or(RunError::Disconnected, RunError::Panicked));
+            }
+        }
+    }
+
+    /// Collects the device's output; see [`DeviceRunner::stream`].
+    pub fn capture<R: Read>(&self, device: R) -> Result<Vec<Line>, RunError> {
+        let mut lines = Vec::new();
+        self.stream(device, |l| lines.push(l))?;
+        Ok(lines)
+    }
+}
+
+/// A test's result; see the [module docs](self#tests).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Ok,
+    Failed,
+    Ignored,
+}
+
+/// A [`Line::Event`] from a device running tests; see the
+/// [module docs](self#tests).
+#[derive(Debug, Clone, PartialEq)]
+pub enum TestEvent {
+    /// How many tests are going to run.
+    Plan(usize),
+    Started(String),
+    Finished {
+        name: String,
+        outcome: Outcome,
+        message: Option<String>,
+    },
+}
+
+impl TestEvent {
+    /// `None` for other lines and events.
+    pub fn from_line(line: &Line) -> Option<Self> {
+        let event = match line {
+            Line::Event(e) => e,
+            Line::Text(_) => return None,
+        };
+
+        if let Some(n) = event.get("tests").and_then(Value::as_u64) {
+            return Some(TestEvent::Plan(n as usize));
+        }
+
+        let name = event.get("test")?.as_str()?.into();
+        let outcome = match event.get("result").map(Value::as_str) {
+            None => return Some(TestEvent::Started(name)),
+            Some(Some("ok")) => Outcome::Ok,
+            Some(Some("failed")) => Outcome::Failed,
+            Some(Some("ignored")) => Outcome::Ignored,
+            Some(_) => return None,
+        };
+        let message = event.get("message").and_then(Value::as_str).map(Into::into);
+
+        Some(TestEvent::Finished {
+            name,
+            outcome,
+            message,
+        })
+    }
+}
+
+// JSON objects are events; anything else (including broken JSON, i.e. from
+// dropped bytes) is text.
+fn parse(line: &str) -> Line {
+    if line.trim_start().starts_with('{') {
+        if let Ok(v @ Value::Object(_)) = serde_json::from_str(line) {
+            return Line::Event(v);
+        }
+    }
+
+    Line::Text(line.into())
+}
+
+/// Opens a serial port (8N1, no flow control) with a short read timeout, for
+/// use with [`DeviceRunner`].
+#[cfg(feature = "serialport")]
+#[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "serialport")))]
+pub fn open_serial(
+    path: &str,
+    baud_rate: u32,
+) -> Result<std::boxed::Box<dyn serialport::SerialPort>, serialport::Error> {
+    use serialport::{DataBits, FlowControl, Parity, StopBits};
+
+    serialport::new(path, baud_rate)
+        .data_bits(DataBits::Eight)
+        .flow_control(FlowControl::None)
+        .parity(Parity::None)
+        .stop_bits(StopBits::One)
+        .timeout(Duration::from_millis(50))
+        .open()
+}
@@ -9,10 +9,10 @@
 )]
 
 pub mod bench;
-pub use bench::Benchmark;
+pub use bench::{prepared, Benchmark, PreparedBenchmark};
 
 pub mod runner;
-pub use runner::{single, suite, BenchmarkRunner};
+pub use runner::{axes, matrix, single, suite, BatchSize, BenchmarkRunner, Config};
 
 pub mod metrics;
 pub use metrics::Metric;
@@ -20,6 +20,10 @@ pub use metrics::Metric;
 pub mod reporters;
 pub use reporters::Reporter;
 
+#[cfg(feature = "device")]
+#[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "device")))]
+pub mod device;
+
 #[cfg(test)]
 #[path = "../examples/common/fib.rs"]
 mod fib;
@@ -84,11 +88,1511 @@ mod tests {
                     .add("two", B)
                     .add("three", |_x: &_| {}),
             )
-            .run(&mut m, &mut r);
+            .add(
+                matrix("grid", axes("size", [1, 2, 3]).axis("align", [1, 4]))
+                    .add("one", |_x: &_| {})
+                    .add("two", B),
+            )
+            .add(
+                matrix(
+                    "cube",
+                    axes("x", 0..2)
+                        .axis("y", ["a", "b"])
+                        .axis("z", [true, false]),
+                )
+                .add("one", |(_x, _y, _z): &(i32, &str, bool)| {}),
+            )
+            .run(&mut m, &mut r)
+            .unwrap();
 
         std::eprintln!("{}", s);
     }
 
+    #[test]
+    fn config_overrides() {
+        use core::cell::Cell;
+
+        let mut m = NoOpMetric;
+        let (single_runs, default_runs, member_runs) = (Cell::new(0), Cell::new(0), Cell::new(0));
+
+        BenchmarkRunner::new()
+            .set_iterations(10)
+            .add(
+                single(
+                    "single",
+                    |_: &u8| single_runs.set(single_runs.get() + 1),
+                    [1, 2],
+                )
+                .with_config(Config::new().iterations(3).warmup(2)),
+            )
+            .add(
+                suite("suite", [1, 2, 3])
+                    .add("default", |_: &u8| default_runs.set(default_runs.get() + 1))
+                    .add_with_config(
+                        "member",
+                        |_: &u8| member_runs.set(member_runs.get() + 1),
+                        Config::new().iterations(4),
+                    )
+                    .with_config(Config::new().warmup(1)),
+            )
+            .run(&mut m, &mut NoOpReporter)
+            .unwrap();
+
+        assert_eq!(single_runs.get(), 2 * (3 + 2));
+        assert_eq!(default_runs.get(), 3 * (10 + 1));
+        assert_eq!(member_runs.get(), 3 * (4 + 1));
+    }
+
+    #[test]
+    fn budgets_cap_iterations() {
+        use core::cell::{Cell, RefCell};
+
+        // Each run "takes" as many units as its input.
+        struct Steps<'c>(&'c Cell<u32>);
+        impl Metric for Steps<'_> {
+            type Unit = u32;
+            type Divisor = u32;
+            type Start = u32;
+
+            fn start(&mut self) -> u32 {
+                self.0.get()
+            }
+            fn end(&mut self, start: u32) -> u32 {
+                self.0.get() - start
+            }
+        }
+
+        // (benchmark, input index, iterations) for each (benchmark, input)
+        // pair, as told to the reporter.
+        #[derive(Default)]
+        struct Counts(RefCell<std::vec::Vec<(&'static str, usize, usize)>>);
+        impl<'c> Reporter<Steps<'c>> for Counts {
+            type Error = core::convert::Infallible;
+
+            fn single_benchmark_iterations(&mut self, input_idx: usize, iterations: usize) {
+                self.0.borrow_mut().push(("single", input_idx, iterations));
+            }
+            fn single_benchmark_run(&mut self, input_idx: usize, _: &dyn Debug, it: usize, _: u32) {
+                let counts = self.0.borrow();
+                let (_, idx, iterations) = counts.last().unwrap();
+                assert_eq!(*idx, input_idx);
+                assert!(it < *iterations);
+            }
+            fn suite_benchmark_run(
+                &mut self,
+                input_idx: usize,
+                _: &dyn Debug,
+                _: usize,
+                name: &'static str,
+                it: usize,
+                iterations: usize,
+                _: u32,
+            ) {
+                if it == 0 {
+                    self.0.borrow_mut().push((name, input_idx, iterations));
+                }
+            }
+        }
+
+        let clock = Cell::new(0);
+        let tick = |n: &u32| clock.set(clock.get() + n);
+        let budget = Config::new().budget(100);
+
+        let mut counts = Counts::default();
+        let mut out = String::new();
+        BenchmarkRunner::new()
+            .set_iterations(10)
+            .add(single("single", tick, [5, 30, 500]).with_config(budget))
+            .add(
+                suite("suite", [5, 30])
+                    .add("unbounded", tick)
+                    .add_with_config("budgeted", tick, budget),
+            )
+            .run(
+                &mut Steps(&clock),
+                &mut (&mut counts, BasicReporter::new_with_fmt_write(&mut out)),
+            )
+            .unwrap();
+
+        assert_eq!(
+            counts.0.into_inner(),
+            [
+                ("budgeted", 0, 10),
+                ("unbounded", 0, 10),
+                ("budgeted", 1, 3),
+                ("unbounded", 1, 10),
+                ("single", 0, 10),
+                ("single", 1, 3),
+                ("single", 2, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn batching() {
+        use core::cell::Cell;
+
+        struct Counting<'c>(&'c Cell<(usize, usize)>);
+        impl Benchmark<u8> for Counting<'_> {
+            type Res = ();
+            fn setup(&mut self, _inp: &u8) {
+                let (setups, runs) = self.0.get();
+                self.0.set((setups + 1, runs));
+            }
+            fn run(&mut self, _inp: &u8) {
+                let (setups, runs) = self.0.get();
+                self.0.set((setups, runs + 1));
+            }
+        }
+
+        let mut m = NoOpMetric;
+        let counts = Cell::new((0, 0));
+        BenchmarkRunner::new()
+            .set_iterations(3)
+            .add(
+                single("fixed", Counting(&counts), [0])
+                    .with_config(Config::new().batch_size(BatchSize::Fixed(5))),
+            )
+            .run(&mut m, &mut NoOpReporter)
+            .unwrap();
+
+        assert_eq!(counts.get(), (3, 3 * 5));
+
+        // `NoOpMetric` always reads the same thing, so picking a batch size
+        // gives up after 8 doublings (1 + 2 + ... + 256 runs) and uses 1:
+        let counts = Cell::new((0, 0));
+        BenchmarkRunner::new()
+            .set_iterations(3)
+            .add(
+                single("auto", Counting(&counts), [0])
+                    .with_config(Config::new().batch_size(BatchSize::Auto)),
+            )
+            .run(&mut m, &mut NoOpReporter)
+            .unwrap();
+        assert_eq!(counts.get(), (9 + 3, 511 + 3));
+
+        // Batch sizes are capped to what fits in the `Divisor`:
+        struct SmallDivisor;
+        impl Metric for SmallDivisor {
+            type Unit = u8;
+            type Divisor = u8;
+            type Start = ();
+            fn start(&mut self) {}
+            fn end(&mut self, (): ()) -> u8 {
+                1
+            }
+        }
+        for batch_size in [BatchSize::Fixed(1000), BatchSize::Auto] {
+            let counts = Cell::new((0, 0));
+            BenchmarkRunner::new()
+                .set_iterations(3)
+                .add(
+                    single("small", Counting(&counts), [0])
+                        .with_config(Config::new().batch_size(batch_size)),
+                )
+                .run(&mut SmallDivisor, &mut NoOpReporter)
+                .unwrap();
+            if batch_size == BatchSize::Fixed(1000) {
+                assert_eq!(counts.get(), (3, 3 * 255));
+            }
+        }
+    }
+
+    #[test]
+    fn prepared_data_is_fresh_for_each_run() {
+        struct SortInPlace;
+        impl PreparedBenchmark<[u8; 4]> for SortInPlace {
+            type Prepared = [u8; 4];
+            type Res = ();
+
+            fn prepare(&mut self, inp: &[u8; 4]) -> [u8; 4] {
+                *inp
+            }
+            fn run(&mut self, inp: &[u8; 4], buf: &mut [u8; 4]) {
+                assert_eq!(inp, buf);
+                buf.sort_unstable();
+            }
+            fn teardown(&mut self, _inp: &[u8; 4], buf: [u8; 4], (): ()) {
+                assert!(buf.windows(2).all(|w| w[0] <= w[1]));
+            }
+        }
+
+        let mut m = NoOpMetric;
+        BenchmarkRunner::new()
+            .set_iterations(3)
+            .add(single(
+                "struct",
+                prepared(SortInPlace),
+                [[4, 3, 2, 1], [1, 3, 2, 4]],
+            ))
+            .add(
+                single("batched", prepared(SortInPlace), [[4, 3, 2, 1]])
+                    .with_config(Config::new().batch_size(BatchSize::Fixed(4))),
+            )
+            .add(
+                single("auto", prepared(SortInPlace), [[2, 1, 4, 3]])
+                    .with_config(Config::new().batch_size(BatchSize::Auto)),
+            )
+            .add(single(
+                "closures",
+                prepared((
+                    |inp: &[u8; 4]| *inp,
+                    |_: &_, buf: &mut [u8; 4]| buf.reverse(),
+                )),
+                [[1, 2, 3, 4]],
+            ))
+            .run(&mut m, &mut NoOpReporter)
+            .unwrap();
+    }
+
+    #[test]
+    fn verification_failures_are_reported() {
+        struct Halve;
+        impl Benchmark<u8> for Halve {
+            type Res = u8;
+            fn run(&mut self, inp: &u8) -> u8 {
+                inp / 2
+            }
+            fn verify(&mut self, inp: &u8, res: &u8) -> Result<(), &'static str> {
+                if res * 2 == *inp {
+                    Ok(())
+                } else {
+                    Err("odd input")
+                }
+            }
+        }
+        impl Benchmark<(u8, u8)> for Halve {
+            type Res = u8;
+            fn run(&mut self, (x, y): &(u8, u8)) -> u8 {
+                self.run(&(x + y))
+            }
+            fn verify(&mut self, (x, y): &(u8, u8), res: &u8) -> Result<(), &'static str> {
+                self.verify(&(x + y), res)
+            }
+        }
+
+        #[derive(Default)]
+        struct Failures(std::vec::Vec<(&'static str, usize, usize)>);
+        impl Reporter<NoOpMetric> for Failures {
+            type Error = core::convert::Infallible;
+
+            fn verification_failed(
+                &mut self,
+                input_idx: usize,
+                _input: &dyn Debug,
+                benchmark_name: &'static str,
+                iteration_idx: usize,
+                message: &'static str,
+            ) {
+                assert_eq!(message, "odd input");
+                self.0.push((benchmark_name, input_idx, iteration_idx));
+            }
+        }
+
+        let runner = || {
+            BenchmarkRunner::new()
+                .set_iterations(3)
+                .add(single("single", Halve, [2, 3, 4]))
+                .add(suite("suite", [1, 2]).add("a", Halve).add("b", |_: &u8| ()))
+                .add(matrix("grid", axes("x", [0, 1]).axis("y", [1])).add("c", Halve))
+        };
+
+        // Benchmarks run last-added first:
+        let mut r = Failures::default();
+        runner().run(&mut NoOpMetric, &mut r).unwrap();
+        assert_eq!(r.0, [("c", 0, 0), ("a", 0, 0), ("single", 1, 0)]);
+
+        let mut s = String::new();
+        let mut r = BasicReporter::new_with_fmt_write(&mut s);
+        runner().run(&mut NoOpMetric, &mut r).unwrap();
+        assert_eq!(s.matches("failed verification:").count(), 2);
+        assert_eq!(s.matches('✗').count(), 2 + 1 + 1);
+        assert!(s.contains("pair(s) failed verification"));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn panics_are_caught() {
+        use core::cell::Cell;
+        use std::vec::Vec;
+
+        #[derive(Default)]
+        struct Panics(Vec<(&'static str, usize, String)>);
+        impl Reporter<NoOpMetric> for Panics {
+            type Error = core::convert::Infallible;
+
+            fn benchmark_panicked(
+                &mut self,
+                input_idx: usize,
+                _input: &dyn Debug,
+                benchmark_name: &'static str,
+                message: &str,
+            ) {
+                self.0.push((benchmark_name, input_idx, message.into()));
+            }
+        }
+
+        let runs = Cell::new(0);
+        let runner = || {
+            runs.set(0);
+            let flaky = |inp: &u8| {
+                runs.set(runs.get() + 1);
+                if *inp == 1 && runs.get() % 3 == 0 {
+                    panic!("flaky on {}", inp);
+                }
+            };
+
+            BenchmarkRunner::new()
+                .set_iterations(3)
+                .set_config(Config::new().catch_panics(true))
+                .add(single("single", flaky, [0, 1, 2]))
+                .add(
+                    suite("suite", [0, 1])
+                        .add("a", |_: &u8| {})
+                        .add("b", |inp: &u8| assert!(*inp == 1, "not one")),
+                )
+                .add(
+                    matrix("grid", axes("x", [0u8, 1]).axis("y", [0u8, 1]))
+                        .add("c", |(x, y): &(u8, u8)| assert!(x != y, "diagonal")),
+                )
+        };
+
+        // Benchmarks run last-added first:
+        let mut r = Panics::default();
+        runner().run(&mut NoOpMetric, &mut r).unwrap();
+        assert_eq!(
+            r.0,
+            [
+                ("c", 0, "diagonal".into()),
+                ("c", 3, "diagonal".into()),
+                ("b", 0, "not one".into()),
+                ("single", 1, "flaky on 1".into()),
+            ]
+        );
+        // The rest of the single benchmark's inputs still ran:
+        assert_eq!(runs.get(), 3 + 3 + 3);
+
+        let mut s = String::new();
+        let mut r = BasicReporter::new_with_fmt_write(&mut s);
+        runner().run(&mut NoOpMetric, &mut r).unwrap();
+        assert_eq!(s.matches("panicked:").count(), 2);
+        assert!(s.contains("pair(s) panicked"));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn binary_reporter_round_trips() {
+        struct Wrong;
+        impl Benchmark<u32> for Wrong {
+            type Res = ();
+            fn run(&mut self, _inp: &u32) {}
+            fn verify(&mut self, inp: &u32, (): &()) -> Result<(), &'static str> {
+                if *inp > 300 {
+                    Err("too big")
+                } else {
+                    Ok(())
+                }
+            }
+        }
+
+        let runner = || {
+            BenchmarkRunner::new()
+                .set_iterations(20)
+                .add(single("single", Wrong, (0..1000).step_by(100)))
+                .add(single("budgeted", B, [1, 2]).with_config(Config::new().budget(5)))
+                .add(
+                    suite("suite", ["a", "bb", "ccc"])
+                        .add("one", B)
+                        .add_with_config("two", B, Config::new().iterations(3))
+                        .add_with_config("three", B, Config::new().budget(4))
+                        .add_with_config(
+                            "boom",
+                            |inp: &&str| assert!(inp.len() != 2, "too long"),
+                            Config::new().catch_panics(true),
+                        ),
+                )
+                .add(matrix("grid", axes("x", [1, 2]).axis("y", ["y", "yy"])).add("c", B))
+        };
+
+        let mut expected = String::new();
+        runner()
+            .run(
+                &mut NoOpMetric,
+                &mut BasicReporter::new_with_fmt_write(&mut expected),
+            )
+            .unwrap();
+
+        let mut wire = std::vec::Vec::new();
+        runner()
+            .run(
+                &mut NoOpMetric,
+                &mut BinaryReporter::new_with_io_write(&mut wire),
+            )
+            .unwrap();
+
+        // Feed it in uneven chunks to make sure frames can span `feed` calls:
+        let mut decoded = String::new();
+        let mut r = BasicReporter::new_with_fmt_write(&mut decoded);
+        let mut decoder = BinaryDecoder::new();
+        for chunk in wire.chunks(7) {
+            decoder.feed::<NoOpMetric, _>(chunk, &mut r).unwrap();
+        }
+
+        assert_eq!(decoded, expected);
+        assert!(wire.len() < expected.len() / 2);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn binary_size_hints_round_trip() {
+        struct Hints(std::vec::Vec<(usize, Option<usize>)>);
+        impl Reporter<NoOpMetric> for Hints {
+            type Error = core::convert::Infallible;
+            fn starting_single_benchmark(
+                &mut self,
+                _: &str,
+                hint: (usize, Option<usize>),
+                _: usize,
+            ) {
+                self.0.push(hint);
+            }
+        }
+
+        let hints = [
+            (0, None),
+            (3, Some(3)),
+            (0, Some(usize::MAX)),
+            (usize::MAX, None),
+        ];
+        let mut wire = std::vec::Vec::new();
+        let mut r = BinaryReporter::new_with_io_write(&mut wire);
+        Reporter::<NoOpMetric>::top_level_benchmarks(&mut r, ["a", "b", "c", "d"].into_iter());
+        for (name, hint) in ["a", "b", "c", "d"].into_iter().zip(hints) {
+            Reporter::<NoOpMetric>::starting_single_benchmark(&mut r, name, hint, 1);
+            Reporter::<NoOpMetric>::ending_single_benchmark(&mut r, name);
+        }
+
+        let mut decoded = Hints(std::vec::Vec::new());
+        BinaryDecoder::new()
+            .feed::<NoOpMetric, _>(&wire, &mut decoded)
+            .unwrap();
+        assert_eq!(decoded.0, hints);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn defmt_logs_decode() {
+        // Fails verification on its third run.
+        struct Third(usize);
+        impl<T: Debug + Any> Benchmark<T> for Third {
+            type Res = ();
+            fn run(&mut self, _inp: &T) {}
+            fn verify(&mut self, _inp: &T, (): &()) -> Result<(), &'static str> {
+                self.0 += 1;
+                if self.0 == 3 {
+                    Err("nope")
+                } else {
+                    Ok(())
+                }
+            }
+        }
+
+        let mut expected = String::new();
+        BenchmarkRunner::new()
+            .set_iterations(2)
+            .add(single("single", B, ["some", "inputs"]))
+            .add(
+                matrix("grid", axes("x", [0, 1]).axis("y", ["a", "b c"]))
+                    .add("one", Third(0))
+                    .add_with_config("two", B, Config::new().iterations(1)),
+            )
+            .run(
+                &mut NoOpMetric,
+                &mut BasicReporter::new_with_fmt_write(&mut expected),
+            )
+            .unwrap();
+
+        // What `defmt-print` shows for `DefmtReporter`'s logs of that run
+        // (which needs a device); with timestamps and another log in the mix.
+        let logs = "\
+            0.000001 ubench-benchmarks 2 unknown
+            0.000001 ubench-benchmark grid
+            0.000001 ubench-benchmark single
+            0.000002 ubench-suite 0 4 4 2
+            0.000002 ubench-member 1 two
+            0.000002 INFO unrelated
+            0.000002 ubench-member 2 one
+            0.000002 ubench-suite-axes 2
+            0.000002 ubench-axis 2 x
+            0.000002 ubench-value 0
+            0.000002 ubench-value 1
+            0.000002 ubench-axis 2 y
+            0.000002 ubench-value \"a\"
+            0.000002 ubench-value \"b c\"
+            0.000003 ubench-input-axes 0
+            0.000003 ubench-input 0 (0, \"a\")
+            0.000003 ubench-suite-run 0 1 1
+            0.000003 ubench-suite-run 1 1 2
+            0.000003 ubench-suite-run 1 1
+            0.000004 ubench-input-axes 1
+            0.000004 ubench-input 1 (0, \"b c\")
+            0.000004 ubench-suite-run 0 1 1
+            0.000004 ubench-failed 0 1 nope
+            0.000004 ubench-suite-run 1 1 2
+            0.000004 ubench-suite-run 1 1
+            0.000005 ubench-input-axes 2
+            0.000005 ubench-input 2 (1, \"a\")
+            0.000005 ubench-suite-run 0 1 1
+            0.000005 ubench-suite-run 1 1 2
+            0.000005 ubench-suite-run 1 1
+            0.000006 ubench-input-axes 3
+            0.000006 ubench-input 3 (1, \"b c\")
+            0.000006 ubench-suite-run 0 1 1
+            0.000006 ubench-suite-run 1 1 2
+            0.000006 ubench-suite-run 1 1
+            0.000006 ubench-end-suite
+            0.000007 ubench-single 1 2 2 2
+            0.000007 ubench-input 0 \"some\"
+            0.000007 ubench-run 1
+            0.000007 ubench-run 1
+            0.000008 ubench-input 1 \"inputs\"
+            0.000008 ubench-run 1
+            0.000008 ubench-run 1
+            0.000008 ubench-end-single
+            0.000009 ubench-ended
+        ";
+
+        let mut decoded = String::new();
+        let mut r = BasicReporter::new_with_fmt_write(&mut decoded);
+        let mut decoder = BinaryDecoder::new();
+        for line in logs.lines() {
+            decoder
+                .feed_defmt_line::<NoOpMetric, _>(line, &mut r)
+                .unwrap();
+        }
+
+        assert_eq!(decoded, expected);
+
+        // Lines going missing:
+        let mut decoder = BinaryDecoder::new();
+        let lines = logs.lines().filter(|l| !l.ends_with("ubench-member 1 two"));
+        let errors = lines
+            .filter_map(|l| {
+                decoder
+                    .feed_defmt_line::<NoOpMetric, _>(l, &mut NoOpReporter)
+                    .err()
+            })
+            .count();
+        assert!(errors > 0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn frame_lines_round_trip() {
+        let runner = || {
+            BenchmarkRunner::new()
+                .set_iterations(5)
+                .add(single("single", B, ["some", "inputs"]))
+                .add(suite("suite", 0..3).add("one", B).add("two", B))
+        };
+
+        let mut expected = String::new();
+        runner()
+            .run(
+                &mut NoOpMetric,
+                &mut BasicReporter::new_with_fmt_write(&mut expected),
+            )
+            .unwrap();
+
+        let mut out = FrameLines::new(String::new());
+        runner()
+            .run(&mut NoOpMetric, &mut BinaryReporter::new(&mut out))
+            .unwrap();
+        // With a panic message in the middle, as on a UART:
+        let wire = out.into_inner().replacen('\n', "\npanicked at 'oops'\n", 1);
+        assert!(wire.lines().all(|l| l.len() < 200));
+
+        let mut decoded = String::new();
+        let mut r = BasicReporter::new_with_fmt_write(&mut decoded);
+        let mut decoder = BinaryDecoder::new();
+        for line in wire.lines() {
+            decoder
+                .feed_frame_line::<NoOpMetric, _>(line, &mut r)
+                .unwrap();
+        }
+
+        assert_eq!(decoded, expected);
+    }
+
+    #[test]
+    fn aggregates_replay_like_the_real_thing() {
+        use core::cell::Cell;
+
+        // Deterministic but not constant:
+        struct Wobbly(u32);
+        impl Metric for Wobbly {
+            type Unit = u32;
+            type Start = ();
+            type Divisor = u32;
+
+            fn start(&mut self) {}
+            fn end(&mut self, (): ()) -> u32 {
+                self.0 = (self.0 * 7 + 3) % 50;
+                self.0 + 1
+            }
+        }
+
+        struct Picky<'c>(&'c Cell<usize>);
+        impl Benchmark<u8> for Picky<'_> {
+            type Res = ();
+            fn run(&mut self, _inp: &u8) {}
+            fn verify(&mut self, inp: &u8, (): &()) -> Result<(), &'static str> {
+                self.0.set(self.0.get() + 1);
+                match (inp, self.0.get() % 4) {
+                    (1, _) => Err("always"),
+                    (2, 0) => Err("sometimes"),
+                    _ => Ok(()),
+                }
+            }
+        }
+
+        let runs = Cell::new(0);
+        let runner = || {
+            runs.set(0);
+            BenchmarkRunner::new()
+                .set_iterations(6)
+                .add(single("single", Picky(&runs), [0, 1, 2]))
+                .add(
+                    suite("suite", [1, 2, 3])
+                        .add("one", Picky(&runs))
+                        .add_with_config("two", B, Config::new().iterations(2)),
+                )
+                .add(matrix("grid", axes("x", [1, 2]).axis("y", ["y", "yy"])).add("c", B))
+        };
+
+        let mut expected = String::new();
+        runner()
+            .run(
+                &mut Wobbly(0),
+                &mut BasicReporter::new_with_fmt_write(&mut expected),
+            )
+            .unwrap();
+
+        let (mut records, mut text) = ([AggregateRecord::EMPTY; 48], [0; 64]);
+        let mut aggregates = AggregatingReporter::new(&mut records, &mut text);
+        runner().run(&mut Wobbly(0), &mut aggregates).unwrap();
+        assert!(!aggregates.overflowed());
+
+        let mut replayed = String::new();
+        aggregates.replay::<Wobbly, _>(&mut BasicReporter::new_with_fmt_write(&mut replayed));
+        assert_eq!(replayed, expected);
+
+        // Running out of room:
+        let mut records = [AggregateRecord::EMPTY; 8];
+        let mut aggregates = AggregatingReporter::new(&mut records, &mut []);
+        runner().run(&mut Wobbly(0), &mut aggregates).unwrap();
+        assert!(aggregates.overflowed());
+        aggregates.replay::<Wobbly, _>(&mut NoOpReporter);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn output_errors_are_returned() {
+        // Takes `n` bytes and then acts like a closed pipe.
+        struct Closing(usize);
+        impl std::io::Write for Closing {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                if buf.len() > self.0 {
+                    return Err(std::io::ErrorKind::BrokenPipe.into());
+                }
+                self.0 -= buf.len();
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut ran = 0;
+        let (mut closing, mut fine) = (Closing(10), String::new());
+        let res = BenchmarkRunner::new()
+            .set_iterations(2)
+            .add(single("single", |_: &u8| ran += 1, inputs(3)))
+            .run(
+                &mut NoOpMetric,
+                &mut (
+                    BasicReporter::new_with_fmt_write(&mut fine),
+                    BasicReporter::new_with_io_write(&mut closing),
+                ),
+            );
+
+        // Every benchmark still runs and the other reporter is unaffected:
+        assert_eq!(ran, 3 * 2);
+        let mut expected = String::new();
+        BenchmarkRunner::new()
+            .set_iterations(2)
+            .add(single("single", B, inputs(3)))
+            .run(
+                &mut NoOpMetric,
+                &mut BasicReporter::new_with_fmt_write(&mut expected),
+            )
+            .unwrap();
+        assert_eq!(fine, expected);
+        match res {
+            Err(EitherError::Second(e)) => assert_eq!(e.kind(), std::io::ErrorKind::BrokenPipe),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn failing_debug_impls_are_cut_short() {
+        #[derive(Clone)]
+        struct Broken;
+        impl core::fmt::Debug for Broken {
+            fn fmt(&self, _: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                Err(core::fmt::Error)
+            }
+        }
+
+        let runner = || {
+            BenchmarkRunner::new()
+                .set_iterations(2)
+                .set_config(Config::new().catch_panics(true))
+                .add(single("single", |_: &Broken| {}, [Broken]))
+                .add(
+                    suite("suite", [Broken, Broken])
+                        .add("fine", |_: &Broken| {})
+                        .add("boom", |_: &Broken| panic!("boom")),
+                )
+        };
+
+        let (mut basic, mut csv, mut md, mut html) =
+            (String::new(), String::new(), String::new(), String::new());
+        let mut wire = std::vec::Vec::new();
+        runner()
+            .run(
+                &mut NoOpMetric,
+                &mut (
+                    (
+                        BasicReporter::new_with_fmt_write(&mut basic),
+                        CsvReporter::new_with_fmt_write(&mut csv, CsvRows::Iterations),
+                    ),
+                    (
+                        (
+                            MarkdownReporter::new_with_fmt_write(&mut md),
+                            HtmlReporter::new_with_fmt_write(&mut html),
+                        ),
+                        BinaryReporter::new_with_io_write(&mut wire),
+                    ),
+                ),
+            )
+            .unwrap();
+
+        assert!(basic.contains("single"));
+        assert!(csv.contains("suite,boom,,,,panicked: boom\r\n"));
+        assert!(md.contains("- ✗ `boom` on ``: panicked: boom\r\n"));
+        assert!(html.ends_with("</body></html>\n"));
+        assert!(!wire.is_empty());
+    }
+
+    #[test]
+    fn buffered_output_only_sends_between_measurements() {
+        use core::cell::Cell;
+
+        // Takes up to 5 bytes at a time, and every other call is "busy".
+        struct Slow<'a> {
+            sent: std::vec::Vec<u8>,
+            busy: bool,
+            measuring: &'a Cell<bool>,
+        }
+        impl Sink for Slow<'_> {
+            type Error = core::convert::Infallible;
+            fn send(&mut self, queued: &[u8]) -> Result<usize, Self::Error> {
+                assert!(!self.measuring.get());
+                self.busy = !self.busy;
+                if self.busy {
+                    return Ok(0);
+                }
+
+                let n = queued.len().min(5);
+                self.sent.extend_from_slice(&queued[..n]);
+                Ok(n)
+            }
+        }
+
+        let measuring = Cell::new(false);
+        let mut expected = String::new();
+        BenchmarkRunner::new()
+            .set_iterations(3)
+            .add(single("single", B, inputs(4)))
+            .add(suite("suite", inputs(2)).add("one", B).add("two", B))
+            .run(
+                &mut NoOpMetric,
+                &mut BasicReporter::new_with_fmt_write(&mut expected),
+            )
+            .unwrap();
+
+        let mut out = BufferedOutput::<_, 64>::new(Slow {
+            sent: std::vec::Vec::new(),
+            busy: false,
+            measuring: &measuring,
+        });
+        BenchmarkRunner::new()
+            .set_iterations(3)
+            .add(single(
+                "single",
+                |_: &u8| {
+                    measuring.set(true);
+                    measuring.set(false)
+                },
+                inputs(4),
+            ))
+            .add(suite("suite", inputs(2)).add("one", B).add("two", B))
+            .run(&mut NoOpMetric, &mut BasicReporter::new(&mut out))
+            .unwrap();
+
+        // Everything got sent once the reporter flushed:
+        assert_eq!(out.queued(), 0);
+        assert_eq!(
+            std::str::from_utf8(&out.into_inner().sent).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "embedded-io")]
+    fn embedded_io_writers_work() {
+        let runner = || {
+            BenchmarkRunner::new()
+                .set_iterations(3)
+                .add(single("single", B, inputs(4)))
+        };
+
+        let mut expected = String::new();
+        runner()
+            .run(
+                &mut NoOpMetric,
+                &mut BasicReporter::new_with_fmt_write(&mut expected),
+            )
+            .unwrap();
+
+        let mut buf = [0; 4096];
+        let mut out = &mut buf[..];
+        runner()
+            .run(
+                &mut NoOpMetric,
+                &mut BasicReporter::new_with_embedded_io(&mut out),
+            )
+            .unwrap();
+        let len = 4096 - out.len();
+        assert_eq!(std::str::from_utf8(&buf[..len]).unwrap(), expected);
+
+        let mut small = [0; 16];
+        let res = runner().run(
+            &mut NoOpMetric,
+            &mut BasicReporter::new_with_embedded_io(&mut &mut small[..]),
+        );
+        assert_eq!(res, Err(embedded_io::SliceWriteError::Full));
+
+        // Async writers, through `Blocking`:
+        #[cfg(feature = "embedded-io-async")]
+        {
+            // Not ready every other poll.
+            struct Lazy(std::vec::Vec<u8>, bool);
+            impl embedded_io::ErrorType for Lazy {
+                type Error = core::convert::Infallible;
+            }
+            impl embedded_io_async::Write for Lazy {
+                async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+                    core::future::poll_fn(|cx| {
+                        self.1 = !self.1;
+                        if self.1 {
+                            cx.waker().wake_by_ref();
+                            return core::task::Poll::Pending;
+                        }
+                        self.0.extend_from_slice(buf);
+                        core::task::Poll::Ready(Ok(buf.len()))
+                    })
+                    .await
+                }
+            }
+
+            let mut out = Blocking(Lazy(std::vec::Vec::new(), false));
+            runner()
+                .run(
+                    &mut NoOpMetric,
+                    &mut BasicReporter::new_with_embedded_io(&mut out),
+                )
+                .unwrap();
+            assert_eq!(std::str::from_utf8(&out.0 .0).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "rtt", feature = "std"))]
+    fn rtt_output_streams_to_the_debugger() {
+        use core::sync::atomic::Ordering::*;
+
+        // Enough output to wrap around the channel's buffer a few times:
+        let runner = || {
+            BenchmarkRunner::new()
+                .set_iterations(3)
+                .add(single("single", B, inputs(100)))
+        };
+
+        let mut expected = String::new();
+        runner()
+            .run(
+                &mut NoOpMetric,
+                &mut BasicReporter::new_with_fmt_write(&mut expected),
+            )
+            .unwrap();
+        assert!(expected.len() > 3 * RttOutput::BUFFER_SIZE);
+
+        let mut out = RttOutput::take().unwrap();
+        assert!(RttOutput::take().is_none());
+
+        // Plays the debugger, reading from the channel's buffer:
+        let len = expected.len();
+        let debugger = std::thread::spawn(move || {
+            let up = &crate::reporters::_SEGGER_RTT.up;
+            let (buf, size) = (up.buf.load(Relaxed), up.size.load(Relaxed));
+
+            let mut got = std::vec::Vec::new();
+            while got.len() < len {
+                let (read, write) = (up.read.load(Relaxed), up.write.load(Acquire));
+                if read != write {
+                    let end = if write > read { write } else { size };
+                    // Safety: `read..end` has been written and won't be
+                    // written again until `read` moves past it.
+                    got.extend_from_slice(unsafe {
+                        std::slice::from_raw_parts(buf.add(read), end - read)
+                    });
+                    up.read.store(end % size, Release);
+                }
+            }
+            got
+        });
+
+        runner()
+            .run(&mut NoOpMetric, &mut BasicReporter::new(&mut out))
+            .unwrap();
+        assert_eq!(
+            std::str::from_utf8(&debugger.join().unwrap()).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "device")]
+    fn device_output_is_read_until_the_end() {
+        use device::*;
+        use std::{collections::VecDeque, io, time::Duration, vec::Vec};
+
+        let lines = DeviceRunner::new()
+            .capture(&b"| text\r\n{\"event\": \"x\", \"n\": 1}\r\n{broken\r\n==========\r\nafter\r\n"[..])
+            .unwrap();
+        assert_eq!(
+            lines,
+            [
+                Line::Text("| text".into()),
+                Line::Event(serde_json::json!({ "event": "x", "n": 1 })),
+                Line::Text("{broken".into()),
+            ]
+        );
+
+        let panicked =
+            DeviceRunner::new().capture(&b"a\n++++++++++\noops\nat 1:2\n++++++++++\nb\n"[..]);
+        assert!(matches!(panicked, Err(RunError::Panicked(m)) if m == "oops\nat 1:2\n"));
+        let ended_early = DeviceRunner::new().capture(&b"a\nb"[..]);
+        assert!(matches!(ended_early, Err(RunError::Disconnected)));
+
+        // Reads that time out (i.e. a serial port's) are retried until the
+        // runner's timeout:
+        struct Drip(VecDeque<io::Result<&'static [u8]>>);
+        impl io::Read for Drip {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                match self.0.pop_front() {
+                    Some(Ok(b)) => {
+                        buf[..b.len()].copy_from_slice(b);
+                        Ok(b.len())
+                    }
+                    Some(Err(e)) => Err(e),
+                    None => Err(io::ErrorKind::TimedOut.into()),
+                }
+            }
+        }
+        let drip = |chunks: Vec<io::Result<&'static [u8]>>| Drip(chunks.into());
+        let runner = DeviceRunner::new().timeout(Duration::from_millis(20));
+
+        let lines = runner
+            .capture(drip(std::vec![
+                Ok(b"hel"),
+                Err(io::ErrorKind::TimedOut.into()),
+                Err(io::ErrorKind::WouldBlock.into()),
+                Ok(b"lo\n=========="),
+                Ok(b"\n"),
+            ]))
+            .unwrap();
+        assert_eq!(lines, [Line::Text("hello".into())]);
+        assert!(matches!(
+            runner.capture(drip(std::vec![Ok(b"hello\n")])),
+            Err(RunError::TimedOut)
+        ));
+        assert!(matches!(
+            runner.capture(drip(std::vec![Err(io::ErrorKind::BrokenPipe.into())])),
+            Err(RunError::Io(e)) if e.kind() == io::ErrorKind::BrokenPipe
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "device")]
+    fn test_events_are_parsed() {
+        use device::*;
+
+        let lines = DeviceRunner::new()
+            .capture(
+                &br#"{"tests": 3}
+{"test": "adds"}
+{"test": "adds", "result": "ok"}
+{"test": "divides", "result": "failed", "message": "by zero"}
+{"test": "later", "result": "ignored"}
+{"test": "odd", "result": "maybe"}
+{"event": "x"}
+test adds ... ok
+==========
+"#[..],
+            )
+            .unwrap();
+
+        let events: std::vec::Vec<_> = lines.iter().map(TestEvent::from_line).collect();
+        assert_eq!(
+            events,
+            [
+                Some(TestEvent::Plan(3)),
+                Some(TestEvent::Started("adds".into())),
+                Some(TestEvent::Finished {
+                    name: "adds".into(),
+                    outcome: Outcome::Ok,
+                    message: None,
+                }),
+                Some(TestEvent::Finished {
+                    name: "divides".into(),
+                    outcome: Outcome::Failed,
+                    message: Some("by zero".into()),
+                }),
+                Some(TestEvent::Finished {
+                    name: "later".into(),
+                    outcome: Outcome::Ignored,
+                    message: None,
+                }),
+                None,
+                None,
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn tee_drives_every_reporter() {
+        let runner = || {
+            BenchmarkRunner::new()
+                .set_iterations(4)
+                .add(single("single", B, inputs(3)))
+                .add(
+                    matrix("grid", axes("x", [1, 2]).axis("y", ['a', 'b']))
+                        .add("one", B)
+                        .add("two", B),
+                )
+        };
+
+        let mut expected = String::new();
+        runner()
+            .run(
+                &mut NoOpMetric,
+                &mut BasicReporter::new_with_fmt_write(&mut expected),
+            )
+            .unwrap();
+
+        let (mut a, mut b, mut c) = (String::new(), String::new(), String::new());
+        let mut first = BasicReporter::new_with_fmt_write(&mut a);
+        runner()
+            .run(
+                &mut NoOpMetric,
+                &mut (
+                    &mut first,
+                    (
+                        BasicReporter::new_with_fmt_write(&mut b),
+                        BasicReporter::new_with_fmt_write(&mut c),
+                    ),
+                ),
+            )
+            .unwrap();
+
+        assert_eq!(a, expected);
+        assert_eq!(b, expected);
+        assert_eq!(c, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn tables_have_a_row_per_entry() {
+        struct OddOnesOut;
+        impl Benchmark<u8> for OddOnesOut {
+            type Res = ();
+            fn run(&mut self, _inp: &u8) {}
+            fn verify(&mut self, inp: &u8, (): &()) -> Result<(), &'static str> {
+                if inp % 2 == 1 {
+                    Err("odd, \"really\"")
+                } else {
+                    Ok(())
+                }
+            }
+        }
+
+        let runner = || {
+            BenchmarkRunner::new()
+                .set_iterations(2)
+                .set_config(Config::new().catch_panics(true))
+                .add(single("s", B, ["a,b"]))
+                .add(
+                    suite("t", 0..2u8)
+                        .add("even", OddOnesOut)
+                        .add("boom", |inp: &u8| assert!(*inp == 0, "not zero")),
+                )
+        };
+
+        let mut csv = String::new();
+        runner()
+            .run(
+                &mut NoOpMetric,
+                &mut CsvReporter::new_with_fmt_write(&mut csv, CsvRows::Iterations),
+            )
+            .unwrap();
+        assert_eq!(
+            csv,
+            "benchmark,member,input,iteration,unknown,status\r\n\
+             t,boom,0,0,1,\r\n\
+             t,boom,0,1,1,\r\n\
+             t,even,0,0,1,\r\n\
+             t,even,0,1,1,\r\n\
+             t,boom,1,,,panicked: not zero\r\n\
+             t,even,1,0,1,\"failed verification: odd, \"\"really\"\"\"\r\n\
+             t,even,1,1,1,\r\n\
+             s,,\"\"\"a,b\"\"\",0,1,\r\n\
+             s,,\"\"\"a,b\"\"\",1,1,\r\n"
+        );
+
+        let mut csv = String::new();
+        runner()
+            .run(
+                &mut NoOpMetric,
+                &mut CsvReporter::new_with_fmt_write(&mut csv, CsvRows::Aggregated),
+            )
+            .unwrap();
+        assert_eq!(
+            csv,
+            "benchmark,member,input,iterations,min unknown,avg unknown,max unknown,status\r\n\
+             t,boom,0,2,1,1,1,\r\n\
+             t,even,0,2,1,1,1,\r\n\
+             t,boom,1,0,,,,panicked: not zero\r\n\
+             t,even,1,2,1,1,1,\"failed verification: odd, \"\"really\"\"\"\r\n\
+             s,,\"\"\"a,b\"\"\",2,1,1,1,\r\n"
+        );
+
+        let mut md = String::new();
+        runner()
+            .run(
+                &mut NoOpMetric,
+                &mut MarkdownReporter::new_with_fmt_write(&mut md),
+            )
+            .unwrap();
+        assert!(md.contains(
+            "| input | boom | even |\r\n\
+             | --- | ---: | ---: |\r\n\
+             | 0 | 1 ± 0 | 1 ± 0 |\r\n\
+             | 1 | ✗ panicked | ✗ 1 ± 0 |\r\n\
+             \r\n\
+             - ✗ `boom` on `1`: panicked: not zero\r\n\
+             - ✗ `even` on `1`: failed verification: odd, \"really\"\r\n"
+        ));
+        assert!(md.contains("| input | s |\r\n| --- | ---: |\r\n| \"a,b\" | 1 ± 0 |\r\n"));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn html_report_is_self_contained() {
+        struct Growing(u32);
+        impl Metric for Growing {
+            type Unit = u32;
+            type Start = ();
+            type Divisor = u32;
+
+            fn start(&mut self) {}
+            fn end(&mut self, (): ()) -> u32 {
+                self.0 += 1;
+                self.0
+            }
+        }
+
+        let mut html = String::new();
+        BenchmarkRunner::new()
+            .set_iterations(5)
+            .set_config(Config::new().catch_panics(true))
+            .add(single("<single>", B, ["a & b", "c"]))
+            .add(
+                suite("suite", 0..3u8)
+                    .add("one", B)
+                    .add("two", |inp: &u8| assert!(*inp != 2, "two")),
+            )
+            .run(
+                &mut Growing(0),
+                &mut HtmlReporter::new_with_fmt_write(&mut html).set_title("results"),
+            )
+            .unwrap();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.trim_end().ends_with("</html>"));
+        assert!(!html.contains("src=") && !html.contains("href=") && !html.contains("<script"));
+
+        // A line chart and a box plot per input, for each top level benchmark:
+        assert_eq!(html.matches("<svg").count(), (1 + 3) + (1 + 2));
+        assert_eq!(html.matches("<polyline").count(), 2 + 1);
+        assert!(html.contains("<h2>&lt;single&gt;</h2>"));
+        assert!(html.contains("<td>&quot;a &amp; b&quot;</td>"));
+        assert!(
+            html.contains("<th>one</th><th>two</th>") || html.contains("<th>two</th><th>one</th>")
+        );
+    }
+
+    #[test]
+    fn progress_counts_up_to_the_end() {
+        let runner = || {
+            BenchmarkRunner::new()
+                .set_iterations(4)
+                .add(single("single", B, inputs(2)))
+                .add(suite("suite", ["a", "b"]).add("one", B).add_with_config(
+                    "two",
+                    B,
+                    Config::new().iterations(2),
+                ))
+        };
+
+        let mut expected = String::new();
+        runner()
+            .run(
+                &mut NoOpMetric,
+                &mut BasicReporter::new_with_fmt_write(&mut expected),
+            )
+            .unwrap();
+
+        let (mut inner, mut beats) = (String::new(), String::new());
+        runner()
+            .run(
+                &mut NoOpMetric,
+                &mut ProgressReporter::new_with_fmt_write(
+                    BasicReporter::new_with_fmt_write(&mut inner),
+                    &mut beats,
+                    ProgressStyle::Heartbeat { every: 3 },
+                ),
+            )
+            .unwrap();
+        assert_eq!(inner, expected);
+        assert_eq!(
+            beats,
+            // `inputs` has no size hint so there's no estimate for `single`:
+            "~ [1/2] suite: input 1/2\r\n\
+             ~ [1/2] suite › one: input 1/2, 25%\r\n\
+             ~ [1/2] suite › one: input 1/2, 50%\r\n\
+             ~ [1/2] suite › one: input 2/2, 75%\r\n\
+             ~ [1/2] suite › one: input 2/2, 100%\r\n\
+             ~ [2/2] single: input 1\r\n\
+             ~ [2/2] single: input 1\r\n\
+             ~ [2/2] single: input 2\r\n"
+        );
+
+        let mut line = String::new();
+        runner()
+            .run(
+                &mut NoOpMetric,
+                &mut ProgressReporter::new_with_fmt_write(
+                    NoOpReporter,
+                    &mut line,
+                    ProgressStyle::StatusLine,
+                ),
+            )
+            .unwrap();
+        assert!(line.contains("[1/2] suite › one: input 2/2, 100%\r\x1b[2K"));
+        assert!(line.ends_with("\r\x1b[2K"));
+
+        // Percentages follow the iteration counts budgets give each input:
+        let mut beats = String::new();
+        BenchmarkRunner::new()
+            .set_iterations(4)
+            .add(single("budgeted", B, ["a", "b"]).with_config(Config::new().budget(2)))
+            .run(
+                &mut NoOpMetric,
+                &mut ProgressReporter::new_with_fmt_write(
+                    NoOpReporter,
+                    &mut beats,
+                    ProgressStyle::Heartbeat { every: 1 },
+                ),
+            )
+            .unwrap();
+        assert_eq!(
+            beats,
+            "~ [1/1] budgeted: input 1/2\r\n\
+             ~ [1/1] budgeted: input 1/2, 16%\r\n\
+             ~ [1/1] budgeted: input 1/2, 33%\r\n\
+             ~ [1/1] budgeted: input 2/2, 75%\r\n\
+             ~ [1/1] budgeted: input 2/2, 100%\r\n"
+        );
+
+        // The status line isn't redrawn for every iteration:
+        let mut line = String::new();
+        BenchmarkRunner::new()
+            .set_iterations(1000)
+            .add(single("many", B, [0]))
+            .run(
+                &mut NoOpMetric,
+                &mut ProgressReporter::new_with_fmt_write(
+                    NoOpReporter,
+                    &mut line,
+                    ProgressStyle::StatusLine,
+                ),
+            )
+            .unwrap();
+        assert!(line.matches("\x1b[2K").count() < 10);
+    }
+
+    #[test]
+    fn plain_output_is_ascii_without_escapes() {
+        struct Wrong;
+        impl Benchmark<char> for Wrong {
+            type Res = ();
+            fn run(&mut self, _inp: &char) {}
+            fn verify(&mut self, _inp: &char, (): &()) -> Result<(), &'static str> {
+                Err("wrong")
+            }
+        }
+
+        let run = |options: FormatOptions| {
+            let mut out = String::new();
+            BenchmarkRunner::new()
+                .add(single("single", Wrong, ['µ']))
+                .add(suite("suite", inputs(2)).add("one", B).add("two", B))
+                .add(matrix("grid", axes("x", [1, 2]).axis("y", [3, 4])).add("c", B))
+                .run(
+                    &mut NoOpMetric,
+                    &mut BasicReporter::new_with_fmt_write(&mut out).set_format_options(options),
+                )
+                .unwrap();
+            out
+        };
+
+        let fancy = run(FormatOptions {
+            colors: true,
+            unicode: true,
+            ..FormatOptions::default()
+        });
+        assert!(fancy.contains('\x1b') && fancy.contains('╔') && fancy.contains('✗'));
+
+        let uncolored = run(FormatOptions {
+            colors: false,
+            unicode: true,
+            ..FormatOptions::default()
+        });
+        assert!(!uncolored.contains('\x1b') && uncolored.contains('╔'));
+
+        let plain = run(FormatOptions::plain());
+        assert!(plain.is_ascii() && !plain.contains('\x1b'));
+        assert!(plain.contains("'u'") && plain.contains("x failed verification: wrong"));
+        // Same layout, just different characters:
+        assert_eq!(plain.lines().count(), uncolored.lines().count());
+        for (p, u) in plain.lines().zip(uncolored.lines()) {
+            assert_eq!(p.len(), u.chars().count() + u.matches('±').count());
+        }
+    }
+
+    #[test]
+    fn output_fits_line_ending_and_width() {
+        let mut out = String::new();
+        BenchmarkRunner::new()
+            .add(single(
+                "a single benchmark with a name too long for one line",
+                B,
+                ["an input that is also much too long to fit"],
+            ))
+            .add(
+                suite("suite", inputs(2))
+                    .add("a suite member with a long name", B)
+                    .add("short", B),
+            )
+            .run(
+                &mut NoOpMetric,
+                &mut BasicReporter::new_with_fmt_write(&mut out).set_format_options(
+                    FormatOptions {
+                        prefix: Some(|w| w.write_str("> ")),
+                        line_ending: "\n",
+                        max_width: Some(30),
+                        ..FormatOptions::plain()
+                    },
+                ),
+            )
+            .unwrap();
+
+        assert!(!out.contains('\r'));
+        assert!(out.contains("| line ") && out.contains("als~`") && out.contains("a suite m~:"));
+        for line in out.lines() {
+            assert!(line.is_empty() || line.starts_with("> "), "{:?}", line);
+            assert!(line.len() <= 30, "{:?}", line);
+        }
+
+        // `\r\n`s split across writes:
+        struct Split;
+        impl Debug for Split {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str("a\r")?;
+                f.write_str("\nb")
+            }
+        }
+
+        let mut out = String::new();
+        BenchmarkRunner::new()
+            .add(single("single", B, [Split]))
+            .run(
+                &mut NoOpMetric,
+                &mut BasicReporter::new_with_fmt_write(&mut out).set_format_options(
+                    FormatOptions {
+                        line_ending: "\n",
+                        ..FormatOptions::plain()
+                    },
+                ),
+            )
+            .unwrap();
+        assert!(!out.contains('\r') && out.contains("a\nb"), "{:?}", out);
+    }
+
+    #[test]
+    fn matrix_covers_the_cartesian_product() {
+        let mut m = NoOpMetric;
+        let mut seen = std::vec::Vec::new();
+
+        BenchmarkRunner::new()
+            .add(
+                matrix("m", axes("a", 0..3).axis("b", ['x', 'y']))
+                    .add("one", |inp: &(u8, char)| seen.push(*inp)),
+            )
+            .run(&mut m, &mut NoOpReporter)
+            .unwrap();
+
+        assert_eq!(
+            seen,
+            [(0, 'x'), (0, 'y'), (1, 'x'), (1, 'y'), (2, 'x'), (2, 'y')]
+        );
+    }
+
     #[test]
     #[cfg(feature = "std")]
     fn fibonacci_example() {
@@ -96,7 +1600,7 @@ mod tests {
 
         let mut out = std::io::stderr();
         let mut m = StdSysTime;
-        let mut r = BasicReporter::new_with_io_write(&mut out);
+        let mut r = BasicReporter::new_with_stderr(&mut out);
 
         BenchmarkRunner::new()
             .set_iterations(50)
@@ -108,6 +1612,7 @@ mod tests {
                     .add("iterative", Iterative)
                     .add("closed form", ClosedForm),
             )
-            .run(&mut m, &mut r);
+            .run(&mut m, &mut r)
+            .unwrap();
     }
 }
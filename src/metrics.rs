@@ -4,7 +4,8 @@ use core::{
 };
 
 pub trait Metric {
-    type Unit: Ord
+    type Unit: RawUnit
+        + Ord
         + Eq
         + Add<Output = Self::Unit>
         + Sub<Output = Self::Unit>
@@ -31,6 +32,44 @@ impl<'m, M: Metric> fmt::Display for MetricFmtAdapter<'m, M> {
     }
 }
 
+/// [`Metric::Unit`]s as plain numbers (of cycles, nanoseconds, etc.).
+///
+/// This is what [`Config::budget`](crate::Config::budget)s are compared
+/// against and what reporters that serialize measurements (i.e.
+/// [`BinaryReporter`](crate::reporters::BinaryReporter)) send to a host.
+/// `from_raw` should undo `to_raw`.
+pub trait RawUnit: Sized {
+    fn to_raw(&self) -> u64;
+    fn from_raw(raw: u64) -> Self;
+}
+
+macro_rules! raw_unit_for_ints {
+    ($($ty:ty),*) => {$(
+        impl RawUnit for $ty {
+            fn to_raw(&self) -> u64 {
+                *self as u64
+            }
+
+            fn from_raw(raw: u64) -> Self {
+                raw as $ty
+            }
+        }
+    )*};
+}
+
+raw_unit_for_ints!(u8, u16, u32, u64, usize);
+
+/// As nanoseconds.
+impl RawUnit for core::time::Duration {
+    fn to_raw(&self) -> u64 {
+        self.as_nanos() as u64
+    }
+
+    fn from_raw(raw: u64) -> Self {
+        core::time::Duration::from_nanos(raw)
+    }
+}
+
 /// A placeholder metric that just returns 1.
 ///
 /// Using this with [`BenchmarkRunner`](crate::BenchmarkRunner) should
@@ -235,5 +274,15 @@ feature_gated! {
                 fmt::Display::fmt(u, f)
             }
         }
+
+        impl crate::metrics::RawUnit for Nanoseconds<u64> {
+            fn to_raw(&self) -> u64 {
+                self.0
+            }
+
+            fn from_raw(raw: u64) -> Self {
+                Nanoseconds(raw)
+            }
+        }
     }
 }
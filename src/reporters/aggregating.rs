@@ -0,0 +1,650 @@
+use core::convert::Infallible;
+use core::fmt::{self, Debug, Write};
+
+use super::Reporter;
+use crate::metrics::Metric;
+use crate::runner::{Axis, AxisValues};
+
+// `axes` takes up to 4 axes.
+const MAX_AXES: usize = 4;
+
+/// A [`Reporter`] that only keeps the min, max, and sum of the measurements
+/// for each (benchmark, input) pair, in caller provided buffers; use
+/// [`AggregatingReporter::replay`] to send the results to another
+/// [`Reporter`] later (i.e. once the UART is free).
+///
+/// `records` holds one entry per benchmark, suite member, input, and
+/// (benchmark, input) pair, plus one per axis and axis value of suites made
+/// with [`matrix`](crate::matrix). Once `records` is full, the rest of the
+/// run is dropped; see [`AggregatingReporter::overflowed`].
+///
+/// Measurements are never formatted. Inputs are only around while they run
+/// though, so `text` holds their `Debug` text (and that of the values of
+/// matrix axes) until the replay. Once `text` is full, nothing more is
+/// formatted and inputs are replayed as `#<index>` (so an empty `text` skips
+/// formatting altogether). Inputs of matrix suites aren't stored at all:
+/// they're put back together from their axes' values.
+///
+/// Replayed runs are synthesized from the aggregates so reporters see the
+/// same min, max, and sum (and therefore average) for each pair.
+pub struct AggregatingReporter<'b, U> {
+    records: &'b mut [AggregateRecord<U>],
+    len: usize,
+    text: &'b mut [u8],
+    text_len: usize,
+    overflowed: bool,
+
+    // Whether the current top level benchmark is a matrix suite.
+    matrix: bool,
+    // The record for the current (benchmark, input) pair, if any.
+    current: Option<usize>,
+    current_input: Option<usize>,
+    // Verification failures for the first iteration arrive before the first
+    // run; they're attached to the pair's record when it is made.
+    pending_failure: Option<&'static str>,
+    // The iteration count for the next input of a single benchmark with a
+    // budget, if any.
+    pending_iterations: Option<usize>,
+}
+
+/// Storage for an [`AggregatingReporter`].
+pub struct AggregateRecord<U>(Entry<U>);
+
+impl<U> AggregateRecord<U> {
+    /// For making arrays of records: `[AggregateRecord::EMPTY; 64]`.
+    pub const EMPTY: Self = AggregateRecord(Entry::Empty);
+}
+
+enum Entry<U> {
+    Empty,
+    TopLevelName(&'static str),
+    Single {
+        name: &'static str,
+        hint: (usize, Option<usize>),
+        iterations: usize,
+    },
+    Suite {
+        name: &'static str,
+        hint: (usize, Option<usize>),
+    },
+    Member {
+        name: &'static str,
+        iterations: usize,
+    },
+    // Followed by an `AxisValue` for each of its values.
+    Axis {
+        name: &'static str,
+        len: usize,
+    },
+    AxisValue(Option<(usize, usize)>),
+    Input {
+        idx: usize,
+        // `None` for inputs of matrix suites, or if `text` was full.
+        text: Option<(usize, usize)>,
+    },
+    Aggregate {
+        input_idx: usize,
+        benchmark_idx: usize,
+        benchmark_name: &'static str,
+        num_iterations: usize,
+
+        count: usize,
+        min: U,
+        max: U,
+        sum: U,
+        // (iteration index, message)
+        failure: Option<(usize, &'static str)>,
+    },
+    Panicked {
+        input_idx: usize,
+        benchmark_name: &'static str,
+        message: Option<(usize, usize)>,
+    },
+    End,
+}
+
+impl<'b, U> AggregatingReporter<'b, U> {
+    pub fn new(records: &'b mut [AggregateRecord<U>], text: &'b mut [u8]) -> Self {
+        AggregatingReporter {
+            records,
+            len: 0,
+            text,
+            text_len: 0,
+            overflowed: false,
+            matrix: false,
+            current: None,
+            current_input: None,
+            pending_failure: None,
+            pending_iterations: None,
+        }
+    }
+
+    /// Whether `records` filled up (and later results were dropped).
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    fn push(&mut self, entry: Entry<U>) -> Option<usize> {
+        if self.overflowed || self.len == self.records.len() {
+            self.overflowed = true;
+            return None;
+        }
+
+        self.records[self.len] = AggregateRecord(entry);
+        self.len += 1;
+        Some(self.len - 1)
+    }
+
+    // Returns the range of `text` the output of `f` went in; `f` isn't called
+    // if `text` is full.
+    fn store_text(
+        &mut self,
+        f: impl FnOnce(&mut dyn Write) -> fmt::Result,
+    ) -> Option<(usize, usize)> {
+        if self.text_len == self.text.len() {
+            return None;
+        }
+
+        struct Arena<'a>(&'a mut [u8], usize);
+        impl Write for Arena<'_> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                // Truncate on `char` boundaries:
+                let free = self.0.len() - self.1;
+                let mut end = s.len().min(free);
+                while !s.is_char_boundary(end) {
+                    end -= 1;
+                }
+
+                self.0[self.1..self.1 + end].copy_from_slice(&s.as_bytes()[..end]);
+                self.1 += end;
+                Ok(())
+            }
+        }
+
+        let start = self.text_len;
+        let mut arena = Arena(self.text, start);
+        let _ = f(&mut arena);
+        self.text_len = arena.1;
+
+        Some((start, self.text_len - start))
+    }
+
+    fn input(&mut self, input_idx: usize, input: &dyn Debug) {
+        if self.current_input != Some(input_idx) {
+            let text = if self.matrix {
+                None
+            } else {
+                self.store_text(|w| write!(w, "{:?}", input))
+            };
+            self.push(Entry::Input {
+                idx: input_idx,
+                text,
+            });
+            self.current_input = Some(input_idx);
+        }
+    }
+
+    fn starting_top_level(&mut self, entry: Entry<U>) {
+        self.push(entry);
+        self.matrix = false;
+        self.current = None;
+        self.current_input = None;
+        self.pending_failure = None;
+        self.pending_iterations = None;
+    }
+
+    fn text(&self, range: Option<(usize, usize)>) -> Text<'_> {
+        // `store_text` only stores whole `char`s.
+        Text(match range {
+            Some((start, len)) => {
+                core::str::from_utf8(&self.text[start..start + len]).unwrap_or("")
+            }
+            None => "",
+        })
+    }
+
+    // The axes of the matrix suite whose `Suite` record is at `suite_idx`.
+    fn suite_axes(&self, suite_idx: usize) -> SuiteAxes {
+        let records = &self.records[..self.len];
+        let mut axes = SuiteAxes {
+            len: 0,
+            axes: [("", 0, 0); MAX_AXES],
+        };
+
+        let mut idx = suite_idx + 1;
+        while let Some(rec) = records.get(idx) {
+            match rec.0 {
+                Entry::Member { .. } => idx += 1,
+                Entry::Axis { name, len } if axes.len < MAX_AXES => {
+                    axes.axes[axes.len] = (name, len, idx + 1);
+                    axes.len += 1;
+                    idx += 1 + len;
+                }
+                _ => break,
+            }
+        }
+
+        axes
+    }
+
+    fn with_axes<T>(&self, axes: &SuiteAxes, f: impl FnOnce(&[Axis<'_>]) -> T) -> T {
+        let records = &self.records[..self.len];
+        let values = axes.axes.map(|(_, len, first)| {
+            // (The values may have been cut off if we ran out of room.)
+            let first = first.min(records.len());
+            StoredValues(self, &records[first..(first + len).min(records.len())])
+        });
+        let len = axes.len;
+        // (One per `MAX_AXES`.)
+        let axes = [0, 1, 2, 3].map(|i| {
+            let (name, len, _) = axes.axes[i];
+            Axis::new(name, len, &values[i])
+        });
+
+        f(&axes[..len])
+    }
+}
+
+impl<'b, U: Copy + Ord + core::ops::Add<Output = U>> AggregatingReporter<'b, U> {
+    #[allow(clippy::too_many_arguments)]
+    fn run(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_idx: usize,
+        benchmark_name: &'static str,
+        iteration_idx: usize,
+        num_iterations: usize,
+        measurement: U,
+    ) {
+        self.input(input_idx, input);
+
+        if iteration_idx != 0 {
+            if let Some(AggregateRecord(Entry::Aggregate {
+                count,
+                min,
+                max,
+                sum,
+                ..
+            })) = self.current.map(|idx| &mut self.records[idx])
+            {
+                *count += 1;
+                *min = (*min).min(measurement);
+                *max = (*max).max(measurement);
+                *sum = *sum + measurement;
+            }
+
+            return;
+        }
+
+        let failure = self.pending_failure.take().map(|msg| (0, msg));
+        self.current = self.push(Entry::Aggregate {
+            input_idx,
+            benchmark_idx,
+            benchmark_name,
+            num_iterations,
+            count: 1,
+            min: measurement,
+            max: measurement,
+            sum: measurement,
+            failure,
+        });
+    }
+}
+
+impl<'b, U> AggregatingReporter<'b, U> {
+    /// Sends everything that was recorded to `reporter`, as if it had been
+    /// there for the run.
+    pub fn replay<M, R>(&self, reporter: &mut R)
+    where
+        M: Metric<Unit = U>,
+        R: Reporter<M>,
+        // Implied by `M: Metric<Unit = U>` but rustc doesn't see that.
+        U: Copy,
+    {
+        let records = &self.records[..self.len];
+        let r = reporter;
+
+        r.top_level_benchmarks(records.iter().filter_map(|rec| match rec.0 {
+            Entry::TopLevelName(name) => Some(name),
+            _ => None,
+        }));
+
+        // (name, is a suite)
+        let mut open = None;
+        // The axes of the current suite, if it's a matrix.
+        let mut axes = None;
+        let mut input = (0, ReplayInput::Index(0));
+        for (idx, rec) in records.iter().enumerate() {
+            match rec.0 {
+                Entry::Empty
+                | Entry::TopLevelName(_)
+                | Entry::Member { .. }
+                | Entry::Axis { .. }
+                | Entry::AxisValue(_) => {}
+                Entry::Single {
+                    name,
+                    hint,
+                    iterations,
+                } => {
+                    r.starting_single_benchmark(name, hint, iterations);
+                    open = Some((name, false));
+                    axes = None;
+                }
+                Entry::Suite { name, hint } => {
+                    let members = records[idx + 1..].iter().map_while(|rec| match rec.0 {
+                        Entry::Member { name, iterations } => Some((name, iterations)),
+                        _ => None,
+                    });
+                    let suite_axes = self.suite_axes(idx);
+                    self.with_axes(&suite_axes, |a| {
+                        r.starting_new_benchmark_suite(name, hint, members, a)
+                    });
+                    open = Some((name, true));
+                    axes = Some(suite_axes).filter(|a| a.len != 0);
+                }
+                Entry::Input { idx, text } => {
+                    input = match (text, &axes) {
+                        (Some(_), _) => (idx, ReplayInput::Text(self.text(text))),
+                        (None, Some(axes)) => {
+                            self.with_axes(axes, |a| r.suite_input_axes(idx, a));
+                            (idx, ReplayInput::Matrix(self, axes.clone(), idx))
+                        }
+                        (None, None) => (idx, ReplayInput::Index(idx)),
+                    };
+                }
+                Entry::Aggregate {
+                    input_idx,
+                    benchmark_idx,
+                    benchmark_name,
+                    num_iterations,
+                    count,
+                    min,
+                    max,
+                    sum,
+                    failure,
+                } => {
+                    let in_suite = matches!(open, Some((_, true)));
+                    if !in_suite && num_iterations != 0 {
+                        r.single_benchmark_iterations(input_idx, num_iterations);
+                    }
+                    synthesize::<M, _>(count, min, max, sum, |it_idx, measurement| {
+                        if let Some((failed_it_idx, msg)) = failure {
+                            if failed_it_idx.min(count - 1) == it_idx {
+                                r.verification_failed(
+                                    input_idx,
+                                    &input.1,
+                                    benchmark_name,
+                                    failed_it_idx,
+                                    msg,
+                                );
+                            }
+                        }
+
+                        if in_suite {
+                            r.suite_benchmark_run(
+                                input_idx,
+                                &input.1,
+                                benchmark_idx,
+                                benchmark_name,
+                                it_idx,
+                                num_iterations,
+                                measurement,
+                            )
+                        } else {
+                            r.single_benchmark_run(input_idx, &input.1, it_idx, measurement)
+                        }
+                    });
+                }
+                Entry::Panicked {
+                    input_idx,
+                    benchmark_name,
+                    message,
+                } => {
+                    r.benchmark_panicked(input_idx, &input.1, benchmark_name, self.text(message).0)
+                }
+                Entry::End => match open.take() {
+                    Some((name, true)) => r.ending_benchmark_suite(name),
+                    Some((name, false)) => r.ending_single_benchmark(name),
+                    None => {}
+                },
+            }
+        }
+
+        // If we ran out of room, close out the benchmark that was running:
+        match open {
+            Some((name, true)) => r.ending_benchmark_suite(name),
+            Some((name, false)) => r.ending_single_benchmark(name),
+            None => {}
+        }
+
+        r.ended();
+    }
+}
+
+// Makes up `count` measurements with the given min, max, and sum.
+fn synthesize<M: Metric, F: FnMut(usize, M::Unit)>(
+    count: usize,
+    min: M::Unit,
+    max: M::Unit,
+    sum: M::Unit,
+    mut f: F,
+) {
+    match count {
+        0 => {}
+        1 => f(0, sum),
+        _ => {
+            f(0, min);
+            f(1, max);
+
+            // Spread the rest out as evenly as we can; these all land between
+            // `min` and `max` since the real measurements did.
+            let mut rest = sum - min - max;
+            for it_idx in 2..count {
+                let remaining: M::Divisor = (count - it_idx).try_into().map_err(|_| ()).unwrap();
+                let m = rest / remaining;
+                rest = rest - m;
+                f(it_idx, m);
+            }
+        }
+    }
+}
+
+// Stored `Debug` text.
+#[derive(Clone, Copy)]
+struct Text<'a>(&'a str);
+impl Debug for Text<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+// (name, len, index of the first `AxisValue` record) for each axis.
+#[derive(Clone)]
+struct SuiteAxes {
+    len: usize,
+    axes: [(&'static str, usize, usize); MAX_AXES],
+}
+
+// The `AxisValue` records of an axis.
+struct StoredValues<'r, 'b, U>(&'r AggregatingReporter<'b, U>, &'r [AggregateRecord<U>]);
+impl<U> AxisValues for StoredValues<'_, '_, U> {
+    fn fmt_value(&self, idx: usize, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.1.get(idx) {
+            Some(AggregateRecord(Entry::AxisValue(text))) => f.write_str(self.0.text(*text).0),
+            _ => Ok(()),
+        }
+    }
+}
+
+enum ReplayInput<'r, 'b, U> {
+    Text(Text<'r>),
+    // No text was stored.
+    Index(usize),
+    // A matrix suite's input: the tuple of its axes' values.
+    Matrix(&'r AggregatingReporter<'b, U>, SuiteAxes, usize),
+}
+
+impl<U> Debug for ReplayInput<'_, '_, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayInput::Text(text) => text.fmt(f),
+            ReplayInput::Index(idx) => write!(f, "#{}", idx),
+            ReplayInput::Matrix(reporter, axes, input_idx) => reporter.with_axes(axes, |axes| {
+                // Like the `Debug` impl for tuples:
+                f.write_str("(")?;
+                for (axis_idx, axis) in axes.iter().enumerate() {
+                    if axis_idx != 0 {
+                        f.write_str(", ")?;
+                    }
+                    let coordinate = Axis::coordinate(axes, *input_idx, axis_idx);
+                    write!(f, "{:?}", axis.value(coordinate))?;
+                }
+                if axes.len() == 1 {
+                    f.write_str(",")?;
+                }
+                f.write_str(")")
+            }),
+        }
+    }
+}
+
+impl<'b, M: Metric> Reporter<M> for AggregatingReporter<'b, M::Unit> {
+    type Error = Infallible;
+
+    fn top_level_benchmarks<I: Iterator<Item = &'static str> + Clone>(&mut self, names: I) {
+        names.for_each(|name| {
+            self.push(Entry::TopLevelName(name));
+        });
+    }
+
+    fn starting_single_benchmark(
+        &mut self,
+        name: &'static str,
+        inputs_size_hint: (usize, Option<usize>),
+        iterations: usize,
+    ) {
+        self.starting_top_level(Entry::Single {
+            name,
+            hint: inputs_size_hint,
+            iterations,
+        });
+    }
+
+    fn single_benchmark_run(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        iteration_idx: usize,
+        measurement: M::Unit,
+    ) {
+        // 0 unless the benchmark has a budget.
+        let iterations = self.pending_iterations.take().unwrap_or(0);
+        self.run(
+            input_idx,
+            input,
+            0,
+            "",
+            iteration_idx,
+            iterations,
+            measurement,
+        );
+    }
+
+    fn single_benchmark_iterations(&mut self, _input_idx: usize, iterations: usize) {
+        self.pending_iterations = Some(iterations);
+    }
+
+    fn ending_single_benchmark(&mut self, _name: &'static str) {
+        self.push(Entry::End);
+    }
+
+    fn starting_new_benchmark_suite<I: Iterator<Item = (&'static str, usize)> + Clone>(
+        &mut self,
+        name: &'static str,
+        inputs_size_hint: (usize, Option<usize>),
+        benchmarks: I,
+        axes: &[Axis<'_>],
+    ) {
+        self.starting_top_level(Entry::Suite {
+            name,
+            hint: inputs_size_hint,
+        });
+        benchmarks.for_each(|(name, iterations)| {
+            self.push(Entry::Member { name, iterations });
+        });
+
+        for axis in axes {
+            self.push(Entry::Axis {
+                name: axis.name,
+                len: axis.len,
+            });
+            for idx in 0..axis.len {
+                let text = self.store_text(|w| write!(w, "{:?}", axis.value(idx)));
+                self.push(Entry::AxisValue(text));
+            }
+        }
+        self.matrix = !axes.is_empty();
+    }
+
+    fn suite_benchmark_run(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_idx: usize,
+        benchmark_name: &'static str,
+        iteration_idx: usize,
+        num_iterations: usize,
+        measurement: M::Unit,
+    ) {
+        self.run(
+            input_idx,
+            input,
+            benchmark_idx,
+            benchmark_name,
+            iteration_idx,
+            num_iterations,
+            measurement,
+        );
+    }
+
+    fn ending_benchmark_suite(&mut self, _name: &'static str) {
+        self.push(Entry::End);
+    }
+
+    fn verification_failed(
+        &mut self,
+        _input_idx: usize,
+        _input: &dyn Debug,
+        _benchmark_name: &'static str,
+        iteration_idx: usize,
+        message: &'static str,
+    ) {
+        if iteration_idx == 0 {
+            self.pending_failure = Some(message);
+        } else if let Some(AggregateRecord(Entry::Aggregate { failure, .. })) =
+            self.current.map(|idx| &mut self.records[idx])
+        {
+            *failure = Some((iteration_idx, message));
+        }
+    }
+
+    fn benchmark_panicked(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_name: &'static str,
+        message: &str,
+    ) {
+        self.input(input_idx, input);
+
+        let message = self.store_text(|w| w.write_str(message));
+        self.push(Entry::Panicked {
+            input_idx,
+            benchmark_name,
+            message,
+        });
+        self.current = None;
+        self.pending_failure = None;
+    }
+}
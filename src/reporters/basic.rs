@@ -5,12 +5,19 @@ use owo_colors::{OwoColorize, Style};
 use super::io::{Output, OutputAdapter, Void};
 use super::Reporter;
 use crate::metrics::{Metric, MetricFmtAdapter};
+use crate::runner::Axis;
 
 pub struct BasicReporter<'o, Out: Output + ?Sized, U = ()> {
     out: OutputAdapter<'o, Out>,
     iterations: usize,
     pub format_options: FormatOptions,
+    filter: FilterState,
     state: State<U>,
+    grid: Option<Grid>,
+    // Verification failure for the (benchmark, input) pair being run, if any.
+    failure: Option<&'static str>,
+    num_failures: usize,
+    num_panics: usize,
 }
 
 #[allow(clippy::needless_lifetimes)]
@@ -20,13 +27,8 @@ impl BasicReporter<'static, Void> {
     // `std::io::Write` impl as trait object
     //
     // `embedded_hal::serial::Write<u8>` impl directly
-    pub fn new<'o, U>(out: &'o mut impl Output) -> BasicReporter<'o, impl Output, U> {
-        BasicReporter {
-            out: OutputAdapter(out),
-            iterations: 0,
-            format_options: Default::default(),
-            state: Default::default(),
-        }
+    pub fn new<'o, U, O: Output>(out: &'o mut O) -> BasicReporter<'o, O, U> {
+        BasicReporter::with_output(out, Default::default())
     }
 
     #[cfg(feature = "embedded-hal")]
@@ -35,40 +37,76 @@ impl BasicReporter<'static, Void> {
     where
         S: embedded_hal::serial::Write<u8>,
     {
-        BasicReporter {
-            out: OutputAdapter(out),
-            iterations: 0,
-            format_options: Default::default(),
-            state: Default::default(),
-        }
+        BasicReporter::with_output(out, Default::default())
     }
 
     pub fn new_with_fmt_write<'o, Fw: fmt::Write, U>(
         out: &'o mut Fw,
     ) -> BasicReporter<'o, dyn fmt::Write + 'o, U> {
-        BasicReporter {
-            out: OutputAdapter(out),
-            iterations: 0,
-            format_options: Default::default(),
-            state: Default::default(),
-        }
+        BasicReporter::with_output(out, Default::default())
     }
 
+    #[cfg(feature = "embedded-io")]
+    #[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "embedded-io")))]
+    pub fn new_with_embedded_io<'o, Ew: embedded_io::Write, U>(
+        out: &'o mut Ew,
+    ) -> BasicReporter<'o, dyn embedded_io::Write<Error = Ew::Error> + 'o, U> {
+        BasicReporter::with_output(out, Default::default())
+    }
+
+    /// `out` could be anything (a file, a pipe, a buffer) so this doesn't
+    /// use colors; use [`new_with_stdout`](BasicReporter::new_with_stdout)
+    /// or [`new_with_stderr`](BasicReporter::new_with_stderr) for output
+    /// that may be going to a terminal, or
+    /// [`set_format_options`](BasicReporter::set_format_options).
     #[cfg(feature = "std")]
     #[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "std")))]
     pub fn new_with_io_write<'o, Iw: std::io::Write, U>(
         out: &'o mut Iw,
     ) -> BasicReporter<'o, dyn std::io::Write + 'o, U> {
+        let options = FormatOptions {
+            colors: false,
+            ..Default::default()
+        };
+        BasicReporter::with_output(out, options)
+    }
+
+    /// Uses [`FormatOptions::detect`] so colors are only used if stdout is a
+    /// terminal.
+    #[cfg(feature = "std")]
+    #[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "std")))]
+    pub fn new_with_stdout<'o, U>(
+        out: &'o mut std::io::Stdout,
+    ) -> BasicReporter<'o, dyn std::io::Write + 'o, U> {
+        BasicReporter::with_output(out, FormatOptions::detect(Stream::Stdout))
+    }
+
+    /// Uses [`FormatOptions::detect`] so colors are only used if stderr is a
+    /// terminal.
+    #[cfg(feature = "std")]
+    #[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "std")))]
+    pub fn new_with_stderr<'o, U>(
+        out: &'o mut std::io::Stderr,
+    ) -> BasicReporter<'o, dyn std::io::Write + 'o, U> {
+        BasicReporter::with_output(out, FormatOptions::detect(Stream::Stderr))
+    }
+}
+
+impl<'o, O: Output + ?Sized, U> BasicReporter<'o, O, U> {
+    fn with_output(out: &'o mut O, format_options: FormatOptions) -> Self {
         BasicReporter {
-            out: OutputAdapter(out),
+            out: OutputAdapter::new(out),
             iterations: 0,
-            format_options: Default::default(),
+            format_options,
+            filter: FilterState::new(),
             state: Default::default(),
+            grid: None,
+            failure: None,
+            num_failures: 0,
+            num_panics: 0,
         }
     }
-}
 
-impl<'o, O: Output + ?Sized, U> BasicReporter<'o, O, U> {
     pub fn set_format_options(mut self, options: FormatOptions) -> Self {
         self.format_options = options;
         self
@@ -90,6 +128,25 @@ pub struct FormatOptions {
     pub min_style: Style,
     pub max_style: Style,
     pub sub_bench_name_style: Style,
+    pub failure_style: Style,
+    /// Whether to print ANSI escapes (for the styles above); when `false`
+    /// the styles are ignored.
+    ///
+    /// The default only checks `NO_COLOR`; see [`FormatOptions::detect`].
+    pub colors: bool,
+    /// Whether to print characters that aren't ASCII; when `false` box
+    /// drawing characters, `±`, `✗`, etc. are swapped for ASCII look-alikes
+    /// (and other non-ASCII characters, i.e. in inputs, become `?`).
+    pub unicode: bool,
+    /// What to end lines with; i.e. `"\n"` if the output already turns
+    /// `\n` into `\r\n` (like some serial HALs do) or is a file.
+    pub line_ending: &'static str,
+    /// How wide lines can get (including the `prefix`); when set, long
+    /// benchmark names are wrapped, long inputs and suite member names are
+    /// cut short, and stats are split across lines to fit.
+    ///
+    /// `matrix` grids and failure messages are not fit to this width.
+    pub max_width: Option<usize>,
 }
 
 impl Default for FormatOptions {
@@ -109,10 +166,91 @@ impl Default for FormatOptions {
             min_style: Style::new().yellow(),
             max_style: Style::new().red(),
             sub_bench_name_style: Style::new().cyan().italic(),
+            failure_style: Style::new().red().bold(),
+            // https://no-color.org
+            #[cfg(feature = "std")]
+            colors: std::env::var_os("NO_COLOR").map_or(true, |v| v.is_empty()),
+            #[cfg(not(feature = "std"))]
+            colors: true,
+            unicode: true,
+            line_ending: "\r\n",
+            max_width: None,
         }
     }
 }
 
+impl FormatOptions {
+    /// No ANSI escapes and ASCII only; for dumb terminals, serial monitors,
+    /// and log files.
+    pub fn plain() -> Self {
+        FormatOptions {
+            colors: false,
+            unicode: false,
+            ..Default::default()
+        }
+    }
+
+    /// Picks `colors` and `unicode` for output that's going to `stream`.
+    ///
+    /// Colors are used if `stream` is a terminal that supports them (as
+    /// decided by `supports-color`, which `owo-colors` uses too; this takes
+    /// `NO_COLOR`, `FORCE_COLOR`, and friends into account). Unicode is used
+    /// unless `TERM` is `dumb` or the locale (`LC_ALL`, `LC_CTYPE`, or
+    /// `LANG`) is set to something that isn't UTF-8.
+    #[cfg(feature = "std")]
+    #[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "std")))]
+    pub fn detect(stream: Stream) -> Self {
+        use std::env::var;
+
+        let colors = terminal(stream).colors;
+
+        let dumb = var("TERM").map_or(false, |t| t == "dumb");
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .find_map(|v| var(v).ok().filter(|l| !l.is_empty()));
+        let utf8 = locale.map_or(true, |l| {
+            let l = l.to_ascii_lowercase();
+            l.contains("utf-8") || l.contains("utf8")
+        });
+
+        FormatOptions {
+            colors,
+            unicode: !dumb && utf8,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "std")))]
+pub use owo_colors::Stream;
+
+// What the `detect` constructors need to know about `stream`.
+#[cfg(feature = "std")]
+pub(crate) struct Terminal {
+    // `stream` is a terminal that ANSI escapes can be used with (`TERM` isn't
+    // `dumb`), whether or not colors are wanted.
+    pub(crate) ansi: bool,
+    // Colors can be used, as decided by `supports-color` (like `owo-colors`
+    // does); this takes `NO_COLOR`, `FORCE_COLOR`, and friends into account.
+    pub(crate) colors: bool,
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn terminal(stream: Stream) -> Terminal {
+    let tty = atty::is(match stream {
+        Stream::Stdout => atty::Stream::Stdout,
+        Stream::Stderr => atty::Stream::Stderr,
+        Stream::Stdin => atty::Stream::Stdin,
+    });
+    let dumb = std::env::var("TERM").map_or(false, |t| t == "dumb");
+
+    Terminal {
+        ansi: tty && !dumb,
+        colors: supports_color::on_cached(stream).map_or(false, |l| l.has_basic),
+    }
+}
+
 #[derive(Debug)]
 enum State<U> {
     WaitingForNextTopLevel,
@@ -158,6 +296,18 @@ impl<U> Default for State<U> {
     }
 }
 
+// Layout for `matrix` suites: the last axis' values are the columns and
+// combinations of the other axes' values are the rows. Each cell in a column
+// has an entry for every benchmark in the suite.
+#[derive(Debug, Clone, Copy)]
+struct Grid {
+    row_label_widths: [usize; 3],
+    num_row_axes: usize,
+    cell_width: usize,
+    num_cols: usize,
+    current_col: usize,
+}
+
 mod support {
     use core::fmt::{self, Display, Write};
     use owo_colors::{OwoColorize, Style};
@@ -222,14 +372,103 @@ mod support {
     }
     impl<A> FmtUtil for A {}
 
+    // Estimates how wide the output of `f` will be.
+    pub(crate) fn estimated_fmt_width(f: impl FnOnce(&mut dyn Write) -> fmt::Result) -> usize {
+        struct Counter(usize);
+        impl Write for Counter {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0 += estimated_str_width(s);
+                Ok(())
+            }
+        }
+
+        let mut c = Counter(0);
+        let _ = f(&mut c);
+        c.0
+    }
+
+    // Estimates how wide `v` will be when printed with `{:?}`.
+    pub(crate) fn estimated_debug_width(v: &dyn fmt::Debug) -> usize {
+        estimated_fmt_width(|c| write!(c, "{:?}", v))
+    }
+
+    // Prints `self.0` (with `Debug` or `Display`), cutting it short with a `…`
+    // if it's wider than `self.1`.
+    pub(crate) struct Truncated<'a, T: ?Sized>(pub &'a T, pub usize);
+
+    impl<T: ?Sized> Truncated<'_, T> {
+        fn fmt_with(
+            &self,
+            f: &mut fmt::Formatter<'_>,
+            print: impl Fn(&mut dyn Write) -> fmt::Result,
+        ) -> fmt::Result {
+            struct Limit<'f, 'a>(&'f mut fmt::Formatter<'a>, usize);
+            impl Write for Limit<'_, '_> {
+                fn write_str(&mut self, s: &str) -> fmt::Result {
+                    for c in s.chars() {
+                        let w = estimated_str_width(c.encode_utf8(&mut [0; 4]));
+                        if w > self.1 {
+                            self.1 = 0;
+                            break;
+                        }
+                        self.1 -= w;
+                        self.0.write_char(c)?;
+                    }
+                    Ok(())
+                }
+            }
+
+            if estimated_fmt_width(&print) <= self.1 {
+                return print(f);
+            }
+            print(&mut Limit(f, self.1.saturating_sub(1)))?;
+            f.write_char('…')
+        }
+    }
+
+    impl<T: fmt::Debug + ?Sized> fmt::Debug for Truncated<'_, T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.fmt_with(f, |w| write!(w, "{:?}", self.0))
+        }
+    }
+
+    impl<T: Display + ?Sized> Display for Truncated<'_, T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.fmt_with(f, |w| write!(w, "{}", self.0))
+        }
+    }
+
+    // Splits `s` into pieces that are at most `width` wide.
+    pub(crate) fn wrapped(s: &str, width: usize) -> impl Iterator<Item = &str> + Clone {
+        let mut rest = s;
+        core::iter::from_fn(move || {
+            if rest.is_empty() {
+                return None;
+            }
+
+            let mut w = 0;
+            let end = rest
+                .char_indices()
+                .find(|(i, c)| {
+                    w += estimated_str_width(c.encode_utf8(&mut [0; 4]));
+                    w > width && *i > 0
+                })
+                .map_or(rest.len(), |(i, _)| i);
+            let (line, r) = rest.split_at(end);
+            rest = r;
+            Some(line)
+        })
+    }
+
     #[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
     pub struct BoxSpec {
         top_left: char,
         top_right: char,
         bot_left: char,
         bot_right: char,
-        vertical: char,
-        horizontal: char,
+        pub(crate) vertical: char,
+        pub(crate) horizontal: char,
+        pub(crate) cross: char,
     }
 
     pub const SINGLE_LINED_BOX: BoxSpec = BoxSpec {
@@ -239,6 +478,7 @@ mod support {
         bot_right: '┘',
         vertical: '│',
         horizontal: '─',
+        cross: '┼',
     };
 
     pub const DOUBLE_LINED_BOX: BoxSpec = BoxSpec {
@@ -248,8 +488,137 @@ mod support {
         bot_right: '╝',
         vertical: '║',
         horizontal: '═',
+        cross: '╬',
     };
 
+    // Everything `BasicReporter` prints goes through this so that ANSI
+    // escapes and non-ASCII characters can be dropped as `FormatOptions`
+    // says. A new one is made for each `write!` so the state that has to
+    // carry over between them lives in the reporter.
+    pub(crate) struct Filtered<'w, W: ?Sized> {
+        out: &'w mut W,
+        colors: bool,
+        unicode: bool,
+        line_ending: &'static str,
+        state: &'w mut FilterState,
+    }
+
+    pub(crate) struct FilterState {
+        escape: Escape,
+        // The last write ended with a `\r` that wasn't written out yet (it
+        // may be the start of a `\r\n`).
+        cr: bool,
+    }
+
+    impl FilterState {
+        pub(crate) const fn new() -> Self {
+            FilterState {
+                escape: Escape::None,
+                cr: false,
+            }
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    enum Escape {
+        None,
+        Start,
+        // Control sequence: `ESC [`, parameters, then a final byte.
+        Csi,
+    }
+
+    impl<'w, W: Write + ?Sized> Filtered<'w, W> {
+        pub(crate) fn new(
+            out: &'w mut W,
+            options: &super::FormatOptions,
+            state: &'w mut FilterState,
+        ) -> Self {
+            Filtered {
+                out,
+                colors: options.colors,
+                unicode: options.unicode,
+                line_ending: options.line_ending,
+                state,
+            }
+        }
+
+        fn filter(&mut self, s: &str) -> fmt::Result {
+            if self.colors && (self.unicode || s.is_ascii()) {
+                return self.out.write_str(s);
+            }
+
+            for c in s.chars() {
+                match (self.state.escape, c) {
+                    (Escape::None, '\x1b') if !self.colors => self.state.escape = Escape::Start,
+                    (Escape::Start, '[') => self.state.escape = Escape::Csi,
+                    (Escape::Start, _) | (Escape::Csi, '\x40'..='\x7e') => {
+                        self.state.escape = Escape::None
+                    }
+                    (Escape::Csi, _) => {}
+                    (Escape::None, c) if self.unicode || c.is_ascii() => self.out.write_char(c)?,
+                    (Escape::None, c) => self.out.write_str(ascii_for(c))?,
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    fn ascii_for(c: char) -> &'static str {
+        match c {
+            '┌' | '┐' | '└' | '┘' | '┼' | '╔' | '╗' | '╚' | '╝' | '╬' => "+",
+            '│' | '║' | '┆' => "|",
+            '─' => "-",
+            '═' => "=",
+            '±' => "+-",
+            '✗' => "x",
+            // Has to stay one wide; see `Truncated`.
+            '…' => "~",
+            'µ' | 'μ' => "u",
+            _ => "?",
+        }
+    }
+
+    impl<W: Write + ?Sized> Write for Filtered<'_, W> {
+        // Lines always end with `\r\n` in what's written here (though the
+        // `\r` and the `\n` may come in separate writes).
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            if self.line_ending == "\r\n" {
+                return self.filter(s);
+            }
+            if s.is_empty() {
+                return Ok(());
+            }
+
+            let mut s = s;
+            if core::mem::take(&mut self.state.cr) {
+                match s.strip_prefix('\n') {
+                    Some(rest) => {
+                        self.out.write_str(self.line_ending)?;
+                        s = rest;
+                    }
+                    None => self.filter("\r")?,
+                }
+            }
+            if let Some(rest) = s.strip_suffix('\r') {
+                self.state.cr = true;
+                s = rest;
+            }
+
+            let mut lines = s.split("\r\n");
+            if let Some(first) = lines.next() {
+                self.filter(first)?;
+            }
+            for line in lines {
+                self.out.write_str(self.line_ending)?;
+                self.filter(line)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn draw_boxed<W: Write>(
         f: &mut W,
         mut prefix: Option<impl FnMut(&mut dyn Write) -> fmt::Result>,
@@ -257,8 +626,17 @@ mod support {
         content: &str,
         box_style: Style,
         content_style: Style,
+        max_width: Option<usize>,
     ) {
-        let lines = content.split_terminator('\n');
+        // Room for the content, past the prefix and the box's sides:
+        let room = match (max_width, prefix.as_mut()) {
+            (Some(m), Some(p)) => m.saturating_sub(estimated_fmt_width(p) + 4).max(1),
+            (Some(m), None) => m.saturating_sub(4).max(1),
+            (None, _) => usize::MAX,
+        };
+        let lines = content
+            .split_terminator('\n')
+            .flat_map(move |l| wrapped(l, room));
         let width = lines.clone().map(estimated_str_width).max().unwrap_or(0);
 
         macro_rules! line {
@@ -266,14 +644,14 @@ mod support {
                 ($($tt:tt)+)
             ),* $(,)?) => {
                 if let Some(ref mut p) = prefix {
-                    p(f).unwrap()
+                    let _ = p(f);
                 }
 
                 $(
-                    write!(f, $($tt)+).unwrap();
+                    let _ = write!(f, $($tt)+);
                 )*
 
-                write!(f, "\r\n").unwrap();
+                let _ = write!(f, "\r\n");
             };
         }
 
@@ -305,12 +683,15 @@ mod support {
 }
 use support::*;
 
+// Output errors are held on to by `OutputAdapter`; errors from the
+// `Display`/`Debug` impls being formatted (i.e. inputs') only cut that value
+// short, like in `Output::output_fmt`.
 macro_rules! prefixed {
     (($self:ident) <- $(
         ($($tt:tt)+)
     ),* $(,)?) => {
-        if let Some(ref mut p) = $self.format_options.prefix {
-            p(&mut $self.out).unwrap()
+        if let Some(p) = $self.format_options.prefix {
+            let _ = p(&mut Filtered::new(&mut $self.out, &$self.format_options, &mut $self.filter));
         }
 
         prefixed![($self) ++  $(
@@ -323,14 +704,73 @@ macro_rules! prefixed {
         ($($tt:tt)+)
     ),* $(,)?) => {
         $(
-            write!($self.out, $($tt)+).unwrap();
+            let _ = write!(Filtered::new(&mut $self.out, &$self.format_options, &mut $self.filter), $($tt)+);
         )*
     }
 }
 
 impl<'o, O: Output + ?Sized, U> BasicReporter<'o, O, U> {
-    fn print_stats<M: Metric<Unit = U>>(&mut self, indent: usize, sum: U, max: U, min: U)
-    where
+    // How much room is left on a line for things past `column` (not counting
+    // the prefix), if lines are limited.
+    fn room_after(&self, column: usize) -> Option<usize> {
+        let max_width = self.format_options.max_width?;
+        let prefix_width = self
+            .format_options
+            .prefix
+            .map_or(0, |p| estimated_fmt_width(|w| p(w)));
+        Some(max_width.saturating_sub(prefix_width + column))
+    }
+
+    fn print_inputs_header<M: Metric>(&mut self, min_iterations: usize, max_iterations: usize) {
+        prefixed![(self) <- ("{}", "Inputs (".dimmed())];
+        if min_iterations != max_iterations {
+            prefixed![(self) ++ (
+                "{}{}",
+                min_iterations.style(self.format_options.iteration_count_style),
+                " to ".dimmed(),
+            )];
+        }
+        prefixed![(self) ++ (
+            "{}{}",
+            max_iterations.style(self.format_options.iteration_count_style),
+            " iterations each,".dimmed(),
+        )];
+
+        // Continue on the next line if it doesn't fit:
+        let width = estimated_fmt_width(|w| {
+            if min_iterations != max_iterations {
+                write!(w, "{} to ", min_iterations)?;
+            }
+            write!(
+                w,
+                "Inputs ({} iterations each, measuring {})",
+                max_iterations,
+                M::UNIT_NAME
+            )
+        });
+        if self.room_after(0).map_or(false, |room| width > room) {
+            prefixed![(self) ++ ("\r\n")];
+            prefixed![(self) <- (" ")];
+        }
+
+        prefixed![(self) ++ (
+            "{}{}{}\r\n",
+            " measuring ".dimmed(),
+            M::UNIT_NAME.style(self.format_options.unit_style),
+            ")".dimmed(),
+        )];
+    }
+
+    // `column` is how much has already been printed on the line (not counting
+    // the prefix).
+    fn print_stats<M: Metric<Unit = U>>(
+        &mut self,
+        column: usize,
+        indent: usize,
+        sum: U,
+        max: U,
+        min: U,
+    ) where
         // rustc can't prove these are already satisfied by the `M: Metric<Unit
         // = U>` impl, for some reason...
         U: core::ops::Div<M::Divisor, Output = U>,
@@ -350,11 +790,33 @@ impl<'o, O: Output + ?Sized, U> BasicReporter<'o, O, U> {
         };
         prefixed![(self) ++
             ("{}", " ".repeat(indent)),
-            ("{} ± {} ",
+            ("{} ± {}",
                 MetricFmtAdapter::<M>(&avg).style(self.format_options.avg_style),
                 MetricFmtAdapter::<M>(&range).style(self.format_options.range_style),
             ),
-            ("{}[{} {} {}]{}",
+        ];
+
+        // Put the range on its own line (lined up with the average) if it
+        // doesn't fit:
+        if let Some(room) = self.room_after(column + indent) {
+            let width = estimated_fmt_width(|w| {
+                write!(
+                    w,
+                    "{} ± {} ([{} to {}])",
+                    MetricFmtAdapter::<M>(&avg),
+                    MetricFmtAdapter::<M>(&range),
+                    MetricFmtAdapter::<M>(&min),
+                    MetricFmtAdapter::<M>(&max),
+                )
+            });
+            if width > room {
+                prefixed![(self) ++ ("\r\n")];
+                prefixed![(self) <- ("{}", " ".repeat(column + indent - 1))];
+            }
+        }
+
+        prefixed![(self) ++
+            (" {}[{} {} {}]{}",
                 "(".dimmed(),
                 MetricFmtAdapter::<M>(&min).style(self.format_options.min_style),
                 "to".dimmed(),
@@ -364,6 +826,241 @@ impl<'o, O: Output + ?Sized, U> BasicReporter<'o, O, U> {
             ("\r\n")
         ];
     }
+
+    // Prints the failures (if any) for the (benchmark, input) pair that just
+    // finished, under its stats.
+    fn print_failures(&mut self, indent: usize, panic: Option<&str>) {
+        if let Some(msg) = self.failure.take() {
+            prefixed![(self) <-
+                ("{}", " ".repeat(indent)),
+                ("{} {}\r\n", "✗ failed verification:".style(self.format_options.failure_style), msg),
+            ];
+        }
+        if let Some(msg) = panic {
+            prefixed![(self) <-
+                ("{}", " ".repeat(indent)),
+                ("{} {}\r\n", "✗ panicked:".style(self.format_options.failure_style), msg),
+            ];
+        }
+    }
+
+    fn print_input(&mut self, input_idx: usize, input: &dyn fmt::Debug, input_num_width: usize) {
+        // Past the number and between the backticks:
+        let room = self.room_after(input_num_width + 5).unwrap_or(usize::MAX);
+        prefixed![(self) <-
+            (" "),
+            ("{: >num_width$}{} ", input_idx + 1, '.'.dimmed(), num_width = input_num_width),
+            ("{}{:?}{}",
+                '`'.dimmed(),
+                Truncated(input, room).style(self.format_options.input_style),
+                '`'.dimmed(),
+            ),
+            ("\r\n"),
+        ];
+    }
+
+    // Right aligned, after the space for the input numbers.
+    fn print_suite_benchmark_name(
+        &mut self,
+        input_num_width: usize,
+        benchmark_name: &'static str,
+        benchmark_name_max_width: usize,
+    ) {
+        let benchmark_name_width = estimated_str_width(benchmark_name);
+        prefixed![(self) <-
+            (" "),
+            ("{: >input_num_width$}  ", "", input_num_width = input_num_width), // Account for the input number alignment
+            ("{}{}{}",
+                ' '.repeat(benchmark_name_max_width.saturating_sub(benchmark_name_width)),
+                Truncated(benchmark_name, benchmark_name_max_width)
+                    .style(self.format_options.sub_bench_name_style),
+                ':'.dimmed(),
+            ),
+        ];
+    }
+
+    fn print_grid_cell<M: Metric<Unit = U>>(
+        &mut self,
+        grid: Grid,
+        sum: U,
+        benchmark_idx: usize,
+        suite_size: usize,
+    ) where
+        U: core::ops::Div<M::Divisor, Output = U>,
+    {
+        let avg: M::Unit = {
+            let count: M::Divisor = self.iterations.try_into().map_err(|_| ()).unwrap();
+            sum / count
+        };
+        let avg = MetricFmtAdapter::<M>(&avg);
+        let failed = self.failure.take().is_some();
+        self.print_grid_cell_contents(
+            grid,
+            &avg.style(self.format_options.avg_style),
+            failed,
+            benchmark_idx,
+            suite_size,
+        );
+    }
+
+    fn print_grid_cell_contents(
+        &mut self,
+        grid: Grid,
+        contents: &dyn fmt::Display,
+        failed: bool,
+        benchmark_idx: usize,
+        suite_size: usize,
+    ) {
+        // Failing cells are marked (their messages are not shown):
+        let mark = if failed { '✗' } else { ' ' };
+        prefixed![(self) ++ (
+            "{}{: >w$}",
+            mark.style(self.format_options.failure_style),
+            contents,
+            w = grid.cell_width,
+        )];
+
+        if benchmark_idx + 1 == suite_size {
+            let spec = self.format_options.suite_box_spec;
+            prefixed![(self) ++ (" {}", spec.vertical.style(self.format_options.suite_box_style))];
+
+            if grid.current_col + 1 == grid.num_cols {
+                prefixed![(self) ++ ("\r\n")];
+            }
+        }
+    }
+
+    // If this is the first run for an input in a suite, prints the input.
+    fn start_suite_input(&mut self, input_idx: usize, input: &dyn fmt::Debug) {
+        use State::*;
+        match self.state {
+            WaitingForNextInputInSuite {
+                suite_size,
+                benchmark_name_max_width,
+                est_num_inputs,
+            } => {
+                self.state = SuiteWaitingForNextBenchmarkForInput {
+                    suite_size,
+                    benchmark_name_max_width,
+                    est_num_inputs,
+                    remaining_benchmarks_for_input: suite_size,
+                };
+
+                // Print the input (unless we're drawing a grid; rows are
+                // labeled in `suite_input_axes` instead):
+                if self.grid.is_none() {
+                    self.print_input(input_idx, input, estimated_num_width(est_num_inputs));
+                }
+            }
+            SuiteWaitingForNextBenchmarkForInput { .. } | RunningBenchmarkInSuite { .. } => {}
+            _ => unreachable!(),
+        }
+    }
+
+    fn end_suite_input_if_done(&mut self) {
+        if let State::SuiteWaitingForNextBenchmarkForInput {
+            remaining_benchmarks_for_input: 0,
+            suite_size,
+            benchmark_name_max_width,
+            est_num_inputs,
+        } = self.state
+        {
+            self.state = State::WaitingForNextInputInSuite {
+                suite_size,
+                benchmark_name_max_width,
+                est_num_inputs,
+            };
+        }
+    }
+
+    fn start_grid<I: Iterator<Item = &'static str> + Clone>(
+        &mut self,
+        axes: &[Axis<'_>],
+        benchmark_names: I,
+    ) {
+        let (col_axis, row_axes) = match axes.split_last() {
+            Some((c, r @ [_, ..])) if r.len() <= 3 => (c, r),
+            // We only lay out 2 to 4 axes as a grid.
+            _ => return,
+        };
+
+        let mut grid = Grid {
+            row_label_widths: [0; 3],
+            num_row_axes: row_axes.len(),
+            cell_width: benchmark_names
+                .clone()
+                .map(estimated_str_width)
+                .max()
+                .unwrap_or(0)
+                .max(10),
+            num_cols: col_axis.len,
+            current_col: 0,
+        };
+        for (a, w) in row_axes.iter().zip(grid.row_label_widths.iter_mut()) {
+            *w = (0..a.len)
+                .map(|i| estimated_debug_width(&a.value(i)))
+                .chain([estimated_str_width(a.name)])
+                .max()
+                .unwrap_or(0);
+        }
+
+        let num_benchmarks = benchmark_names.clone().count().max(1);
+        let header_width =
+            |i| estimated_str_width(col_axis.name) + 3 + estimated_debug_width(&col_axis.value(i));
+        let widest_header = (0..col_axis.len).map(header_width).max().unwrap_or(0);
+        grid.cell_width = grid
+            .cell_width
+            .max((widest_header + num_benchmarks) / num_benchmarks);
+        let span = num_benchmarks * (grid.cell_width + 1) - 1;
+
+        let spec = self.format_options.suite_box_spec;
+        let box_style = self.format_options.suite_box_style;
+        let row_label_width = grid.row_label_width();
+
+        // Column headers:
+        prefixed![(self) <- (" ")];
+        for (idx, (a, w)) in row_axes.iter().zip(grid.row_label_widths).enumerate() {
+            let sep = if idx == 0 { "" } else { ", " };
+            prefixed![(self) ++ ("{}{: >w$}", sep.dimmed(), a.name.dimmed(), w = w)];
+        }
+        prefixed![(self) ++ (" {}", spec.vertical.style(box_style))];
+        for i in 0..col_axis.len {
+            let v = col_axis.value(i);
+            prefixed![(self) ++
+                (" {}{}{:?}", col_axis.name.dimmed(), " = ".dimmed(), v.style(self.format_options.input_style)),
+                ("{} {}", " ".repeat(span - header_width(i)), spec.vertical.style(box_style)),
+            ];
+        }
+        prefixed![(self) ++ ("\r\n")];
+
+        // Benchmark names:
+        prefixed![(self) <- (" {} {}", " ".repeat(row_label_width), spec.vertical.style(box_style))];
+        for _ in 0..col_axis.len {
+            for name in benchmark_names.clone() {
+                prefixed![(self) ++ (" {: >w$}", name.style(self.format_options.sub_bench_name_style), w = grid.cell_width)];
+            }
+            prefixed![(self) ++ (" {}", spec.vertical.style(box_style))];
+        }
+        prefixed![(self) ++ ("\r\n")];
+
+        // Separator:
+        prefixed![(self) <- ("{}", spec.horizontal.repeat(row_label_width + 2).style(box_style))];
+        for _ in 0..col_axis.len {
+            prefixed![(self) ++ ("{}", spec.cross.join(spec.horizontal.repeat(span + 2)).style(box_style))];
+        }
+        prefixed![(self) ++ ("{}\r\n", spec.cross.style(box_style))];
+
+        self.grid = Some(grid);
+    }
+}
+
+impl Grid {
+    fn row_label_width(&self) -> usize {
+        self.row_label_widths[..self.num_row_axes]
+            .iter()
+            .sum::<usize>()
+            + 2 * (self.num_row_axes - 1)
+    }
 }
 
 impl<'o, O, M> Reporter<M> for BasicReporter<'o, O, M::Unit>
@@ -371,41 +1068,41 @@ where
     O: Output + ?Sized,
     M: Metric,
 {
+    type Error = O::Error;
+
     fn top_level_benchmarks<I: Iterator<Item = &'static str> + Clone>(&mut self, _names: I) {
         prefixed![(self) ++ ("\r\n\r\n")];
     }
-    fn num_iterations(&mut self, iterations: usize) {
-        debug_assert!(iterations > 0);
-        self.iterations = iterations;
-    }
 
     fn starting_single_benchmark(
         &mut self,
         name: &'static str,
         input_size_hint: (usize, Option<usize>),
+        iterations: usize,
     ) {
         debug_assert!(matches!(self.state, State::WaitingForNextTopLevel));
+        debug_assert!(iterations > 0);
+        self.iterations = iterations;
         self.state = State::WaitingForNextSingleBenchmark {
             est_num_inputs: input_size_hint.1.unwrap_or(input_size_hint.0),
         };
 
         draw_boxed(
-            &mut self.out,
+            &mut Filtered::new(&mut self.out, &self.format_options, &mut self.filter),
             self.format_options.prefix,
             &self.format_options.single_box_spec,
             name,
             self.format_options.single_box_style,
             self.format_options.top_level_bench_name_style,
+            self.format_options.max_width,
         );
         prefixed![(self) <- ("\r\n")];
-        prefixed![(self) <- (
-            "{}{}{}{}{}\r\n",
-            "Inputs (".dimmed(),
-            self.iterations.style(self.format_options.iteration_count_style),
-            " iterations each, measuring ".dimmed(),
-            M::UNIT_NAME.style(self.format_options.unit_style),
-            ")".dimmed(),
-        )];
+        self.print_inputs_header::<M>(self.iterations, self.iterations);
+    }
+
+    fn single_benchmark_iterations(&mut self, _input_idx: usize, iterations: usize) {
+        debug_assert!(iterations > 0);
+        self.iterations = iterations;
     }
 
     fn single_benchmark_run(
@@ -455,16 +1152,18 @@ where
 
             // First print the input:
             let input_num_width = estimated_num_width(est_num_inputs);
-            prefixed![(self) <-
-                (" "),
-                ("{: >num_width$}{} ", input_idx + 1, '.'.dimmed(), num_width = input_num_width),
-                ("{}{:?}{}", '`'.dimmed(), input.style(self.format_options.input_style), '`'.dimmed()),
-                ("\r\n"),
-            ];
+            self.print_input(input_idx, input, input_num_width);
 
             // Next print the stats:
             prefixed![(self) <- (" ")];
-            self.print_stats::<M>(input_num_width + 2, current_sum, current_max, current_min);
+            self.print_stats::<M>(
+                1,
+                input_num_width + 2,
+                current_sum,
+                current_max,
+                current_min,
+            );
+            self.print_failures(input_num_width + 3, None);
 
             // We'll either get another input or we'll end the single benchmark.
             self.state = State::WaitingForNextSingleBenchmark { est_num_inputs };
@@ -482,35 +1181,83 @@ where
         prefixed![(self) ++ ("\r\n\r\n")];
     }
 
-    fn starting_new_benchmark_suite<I: Iterator<Item = &'static str> + Clone>(
+    fn starting_new_benchmark_suite<I: Iterator<Item = (&'static str, usize)> + Clone>(
         &mut self,
         name: &'static str,
         input_size_hint: (usize, Option<usize>),
-        benchmark_names: I,
+        benchmarks: I,
+        axes: &[Axis<'_>],
     ) {
         debug_assert!(matches!(self.state, State::WaitingForNextTopLevel));
+        let benchmark_names = benchmarks.clone().map(|(name, _)| name);
         self.state = State::WaitingForNextInputInSuite {
             suite_size: benchmark_names.clone().count(),
-            benchmark_name_max_width: benchmark_names.map(estimated_str_width).max().unwrap_or(0),
+            benchmark_name_max_width: {
+                let widest = benchmark_names
+                    .clone()
+                    .map(estimated_str_width)
+                    .max()
+                    .unwrap_or(0);
+                // Leave most of a limited line for the stats:
+                match self.format_options.max_width {
+                    Some(max_width) => widest.min((max_width / 3).max(1)),
+                    None => widest,
+                }
+            },
             est_num_inputs: input_size_hint.1.unwrap_or(input_size_hint.0),
         };
 
         draw_boxed(
-            &mut self.out,
+            &mut Filtered::new(&mut self.out, &self.format_options, &mut self.filter),
             self.format_options.prefix,
             &self.format_options.suite_box_spec,
             name,
             self.format_options.suite_box_style,
             self.format_options.top_level_bench_name_style,
+            self.format_options.max_width,
         );
         prefixed![(self) <- ("\r\n")];
-        prefixed![(self) <- (
-            "{}{}{}{}{}\r\n",
-            "Inputs (".dimmed(),
-            self.iterations.style(self.format_options.iteration_count_style),
-            " iterations each, measuring ".dimmed(),
-            M::UNIT_NAME.style(self.format_options.unit_style),
-            ")".dimmed(),
+
+        // Benchmarks in a suite can have different iteration counts:
+        let min_iterations = benchmarks.clone().map(|(_, it)| it).min().unwrap_or(0);
+        let max_iterations = benchmarks.map(|(_, it)| it).max().unwrap_or(0);
+        self.print_inputs_header::<M>(min_iterations, max_iterations);
+
+        if !axes.is_empty() {
+            prefixed![(self) <- ("\r\n")];
+            self.start_grid(axes, benchmark_names);
+        }
+    }
+
+    fn suite_input_axes(&mut self, input_idx: usize, axes: &[Axis<'_>]) {
+        let grid = if let Some(ref mut g) = self.grid {
+            g
+        } else {
+            return;
+        };
+
+        grid.current_col = Axis::coordinate(axes, input_idx, axes.len() - 1);
+        if grid.current_col != 0 {
+            return;
+        }
+
+        // Starting a new row; print its label:
+        let grid = *grid;
+        prefixed![(self) <- (" ")];
+        for (idx, w) in grid.row_label_widths[..grid.num_row_axes]
+            .iter()
+            .enumerate()
+        {
+            let v = axes[idx].value(Axis::coordinate(axes, input_idx, idx));
+            let sep = if idx == 0 { "" } else { ", " };
+            prefixed![(self) ++
+                ("{}{}", sep.dimmed(), " ".repeat(w - estimated_debug_width(&v))),
+                ("{:?}", v.style(self.format_options.input_style)),
+            ];
+        }
+        prefixed![(self) ++ (
+            " {}",
+            self.format_options.suite_box_spec.vertical.style(self.format_options.suite_box_style),
         )];
     }
 
@@ -521,39 +1268,16 @@ where
         benchmark_idx: usize,
         benchmark_name: &'static str,
         iteration_idx: usize,
+        num_iterations: usize,
         measurement: M::Unit,
     ) {
         use State::*;
 
         // First, handle the case where we just started a new input in the suite:
-        match self.state {
-            WaitingForNextInputInSuite {
-                suite_size,
-                benchmark_name_max_width,
-                est_num_inputs,
-            } => {
-                debug_assert_eq!(benchmark_idx, 0);
-                self.state = SuiteWaitingForNextBenchmarkForInput {
-                    suite_size,
-                    benchmark_name_max_width,
-                    est_num_inputs,
-                    remaining_benchmarks_for_input: suite_size,
-                };
-
-                // Print the input:
-                let input_num_width = estimated_num_width(est_num_inputs);
-                prefixed![(self) <-
-                    (" "),
-                    ("{: >num_width$}{} ", input_idx + 1, '.'.dimmed(), num_width = input_num_width),
-                    ("{}{:?}{}", '`'.dimmed(), input.style(self.format_options.input_style), '`'.dimmed()),
-                    ("\r\n"),
-                ];
-            }
-            SuiteWaitingForNextBenchmarkForInput { .. } | RunningBenchmarkInSuite { .. } => {
-                /* handled below */
-            }
-            _ => unreachable!(),
-        }
+        debug_assert!(
+            benchmark_idx == 0 || !matches!(self.state, WaitingForNextInputInSuite { .. })
+        );
+        self.start_suite_input(input_idx, input);
 
         // Next, handle the case where we just started a new benchmark for an input:
         match &mut self.state {
@@ -565,6 +1289,8 @@ where
             } => {
                 debug_assert_eq!(iteration_idx, 0);
                 debug_assert_eq!(*remaining_benchmarks_for_input + benchmark_idx, *suite_size);
+                debug_assert!(num_iterations > 0);
+                self.iterations = num_iterations;
                 self.state = RunningBenchmarkInSuite {
                     suite_size: *suite_size,
                     benchmark_name_max_width: *benchmark_name_max_width,
@@ -608,22 +1334,28 @@ where
         } = self.state
         {
             // We're done with this benchmark which means its time to print a line!
-
-            // First print the benchmark's name, right aligned:
-            let input_num_width = estimated_num_width(est_num_inputs);
-            let benchmark_name_width = estimated_str_width(benchmark_name);
-            prefixed![(self) <-
-                (" "),
-                ("{: >input_num_width$}  ", "", input_num_width = input_num_width), // Account for the input number alignment
-                ("{}{}{}",
-                    ' '.repeat(benchmark_name_max_width - benchmark_name_width),
-                    benchmark_name.style(self.format_options.sub_bench_name_style),
-                    ':'.dimmed(),
-                ),
-            ];
-
-            // And then the stats:
-            self.print_stats::<M>(1, current_sum, current_max, current_min);
+            if let Some(grid) = self.grid {
+                // ...or, for grids, a cell:
+                self.print_grid_cell::<M>(grid, current_sum, benchmark_idx, suite_size);
+            } else {
+                // First print the benchmark's name, right aligned:
+                let input_num_width = estimated_num_width(est_num_inputs);
+                self.print_suite_benchmark_name(
+                    input_num_width,
+                    benchmark_name,
+                    benchmark_name_max_width,
+                );
+
+                // And then the stats:
+                self.print_stats::<M>(
+                    input_num_width + benchmark_name_max_width + 4,
+                    1,
+                    current_sum,
+                    current_max,
+                    current_min,
+                );
+                self.print_failures(input_num_width + benchmark_name_max_width + 5, None);
+            }
 
             // Now, update the state to indicate that we're waitin for the next
             // benchmark for this input:
@@ -637,19 +1369,7 @@ where
 
         // And finally, the case where we've finished all the benchmarks for an input
         // and need to move on to the next (potential) input:
-        if let SuiteWaitingForNextBenchmarkForInput {
-            remaining_benchmarks_for_input: 0,
-            suite_size,
-            benchmark_name_max_width,
-            est_num_inputs,
-        } = self.state
-        {
-            self.state = WaitingForNextInputInSuite {
-                suite_size,
-                benchmark_name_max_width,
-                est_num_inputs,
-            };
-        }
+        self.end_suite_input_if_done();
     }
 
     fn ending_benchmark_suite(&mut self, _name: &'static str) {
@@ -658,12 +1378,136 @@ where
             State::WaitingForNextInputInSuite { .. }
         ));
         self.state = State::WaitingForNextTopLevel;
+        self.grid = None;
 
         prefixed![(self) <- ("\r\n")];
         prefixed![(self) ++ ("\r\n\r\n")];
     }
 
+    fn verification_failed(
+        &mut self,
+        _input_idx: usize,
+        _input: &dyn fmt::Debug,
+        _benchmark_name: &'static str,
+        _iteration_idx: usize,
+        message: &'static str,
+    ) {
+        // Printed once the (benchmark, input) pair is done running:
+        self.failure = Some(message);
+        self.num_failures += 1;
+    }
+
+    fn benchmark_panicked(
+        &mut self,
+        input_idx: usize,
+        input: &dyn fmt::Debug,
+        benchmark_name: &'static str,
+        message: &str,
+    ) {
+        use State::*;
+        self.num_panics += 1;
+
+        // Single benchmarks: print the input (whether or not some iterations
+        // already ran) and move on to the next one.
+        if let WaitingForNextSingleBenchmark { est_num_inputs }
+        | RunningSingleBenchmark { est_num_inputs, .. } = self.state
+        {
+            let input_num_width = estimated_num_width(est_num_inputs);
+            self.print_input(input_idx, input, input_num_width);
+            self.print_failures(input_num_width + 3, Some(message));
+
+            self.state = WaitingForNextSingleBenchmark { est_num_inputs };
+            return;
+        }
+
+        // Suites: this (benchmark, input) pair is done; print its entry.
+        self.start_suite_input(input_idx, input);
+        let (suite_size, benchmark_name_max_width, est_num_inputs, remaining_benchmarks_for_input) =
+            match self.state {
+                SuiteWaitingForNextBenchmarkForInput {
+                    suite_size,
+                    benchmark_name_max_width,
+                    est_num_inputs,
+                    remaining_benchmarks_for_input,
+                } => (
+                    suite_size,
+                    benchmark_name_max_width,
+                    est_num_inputs,
+                    remaining_benchmarks_for_input - 1,
+                ),
+                RunningBenchmarkInSuite {
+                    suite_size,
+                    benchmark_name_max_width,
+                    est_num_inputs,
+                    remaining_benchmarks_for_input,
+                    ..
+                } => (
+                    suite_size,
+                    benchmark_name_max_width,
+                    est_num_inputs,
+                    remaining_benchmarks_for_input,
+                ),
+                _ => unreachable!(),
+            };
+
+        if let Some(grid) = self.grid {
+            let benchmark_idx = suite_size - remaining_benchmarks_for_input - 1;
+            self.failure = None;
+            self.print_grid_cell_contents(
+                grid,
+                &'-'.style(self.format_options.failure_style),
+                true,
+                benchmark_idx,
+                suite_size,
+            );
+        } else {
+            let input_num_width = estimated_num_width(est_num_inputs);
+            self.print_suite_benchmark_name(
+                input_num_width,
+                benchmark_name,
+                benchmark_name_max_width,
+            );
+            prefixed![(self) ++ (
+                " {} {}\r\n",
+                "✗ panicked:".style(self.format_options.failure_style),
+                message,
+            )];
+            self.print_failures(input_num_width + benchmark_name_max_width + 5, None);
+        }
+
+        self.state = SuiteWaitingForNextBenchmarkForInput {
+            suite_size,
+            benchmark_name_max_width,
+            est_num_inputs,
+            remaining_benchmarks_for_input,
+        };
+        self.end_suite_input_if_done();
+    }
+
     fn ended(&mut self) {
         debug_assert!(matches!(self.state, State::WaitingForNextTopLevel { .. }));
+
+        if self.num_failures != 0 {
+            prefixed![(self) <- (
+                "{} {}{}\r\n",
+                '✗'.style(self.format_options.failure_style),
+                self.num_failures.style(self.format_options.failure_style),
+                " (benchmark, input) pair(s) failed verification".dimmed(),
+            )];
+        }
+        if self.num_panics != 0 {
+            prefixed![(self) <- (
+                "{} {}{}\r\n",
+                '✗'.style(self.format_options.failure_style),
+                self.num_panics.style(self.format_options.failure_style),
+                " (benchmark, input) pair(s) panicked".dimmed(),
+            )];
+        }
+
+        self.out.flush();
+    }
+
+    fn take_error(&mut self) -> Option<Self::Error> {
+        self.out.take_error()
     }
 }
@@ -0,0 +1,1048 @@
+//! A compact binary [`Reporter`] for slow links (i.e. UARTs), and a host side
+//! decoder ([`BinaryDecoder`], `std` only) that turns its output (or the logs
+//! of a `DefmtReporter`) back into [`Reporter`] calls.
+//!
+//! ## Format
+//!
+//! Every [`Reporter`] call is sent as one frame: [COBS] encoded and terminated
+//! by a `0x00` byte. A frame is a tag byte identifying the call followed by
+//! its arguments; integers (including measurements, see [`RawUnit`]) are
+//! LEB128 varints and strings are a varint length followed by UTF-8.
+//!
+//! Benchmark names are sent once (in the `top_level_benchmarks` and
+//! `starting_new_benchmark_suite` frames) and are referred to by index after
+//! that; failures in single benchmarks don't need one. Inputs are sent once (as `Debug` text), before the first frame that
+//! refers to them; frames for runs are just the measurement (and, in suites,
+//! the benchmark's index and, for the first run, the iteration count) since
+//! they always refer to the last input sent and iterations arrive in order.
+//!
+//! [COBS]: https://en.wikipedia.org/wiki/Consistent_Overhead_Byte_Stuffing
+
+use core::fmt::{self, Debug, Write};
+
+use super::io::{Output, OutputAdapter, Void};
+use super::Reporter;
+use crate::metrics::{Metric, RawUnit};
+use crate::runner::Axis;
+
+mod tag {
+    pub(super) const TOP_LEVEL_BENCHMARKS: u8 = 1;
+    pub(super) const STARTING_SINGLE: u8 = 2;
+    pub(super) const INPUT: u8 = 3;
+    pub(super) const SINGLE_RUN: u8 = 4;
+    pub(super) const ENDING_SINGLE: u8 = 5;
+    pub(super) const STARTING_SUITE: u8 = 6;
+    pub(super) const SUITE_INPUT_AXES: u8 = 7;
+    pub(super) const SUITE_RUN: u8 = 8;
+    pub(super) const ENDING_SUITE: u8 = 9;
+    pub(super) const VERIFICATION_FAILED: u8 = 10;
+    pub(super) const PANICKED: u8 = 11;
+    pub(super) const ENDED: u8 = 12;
+    pub(super) const SINGLE_ITERATIONS: u8 = 13;
+}
+
+pub struct BinaryReporter<'o, Out: Output + ?Sized> {
+    out: OutputAdapter<'o, Out>,
+    frame: Cobs,
+    // Index (in `top_level_benchmarks` order) of the next top level benchmark.
+    next_top_level: usize,
+    // The last input sent for the current top level benchmark.
+    current_input: Option<usize>,
+    members: SuiteMembers,
+}
+
+#[allow(clippy::needless_lifetimes)]
+impl BinaryReporter<'static, Void> {
+    pub fn new<'o, O: Output>(out: &'o mut O) -> BinaryReporter<'o, O> {
+        BinaryReporter::with_output(out)
+    }
+
+    #[cfg(feature = "embedded-hal")]
+    #[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "embedded-hal")))]
+    pub fn new_with_serial<'o, S>(out: &'o mut S) -> BinaryReporter<'o, S>
+    where
+        S: embedded_hal::serial::Write<u8>,
+    {
+        BinaryReporter::with_output(out)
+    }
+
+    #[cfg(feature = "embedded-io")]
+    #[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "embedded-io")))]
+    pub fn new_with_embedded_io<'o, Ew: embedded_io::Write>(
+        out: &'o mut Ew,
+    ) -> BinaryReporter<'o, dyn embedded_io::Write<Error = Ew::Error> + 'o> {
+        BinaryReporter::with_output(out)
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "std")))]
+    pub fn new_with_io_write<'o, Iw: std::io::Write>(
+        out: &'o mut Iw,
+    ) -> BinaryReporter<'o, dyn std::io::Write + 'o> {
+        BinaryReporter::with_output(out)
+    }
+}
+
+impl<'o, O: Output + ?Sized> BinaryReporter<'o, O> {
+    fn with_output(out: &'o mut O) -> Self {
+        BinaryReporter {
+            out: OutputAdapter::new(out),
+            frame: Cobs::new(),
+            next_top_level: 0,
+            current_input: None,
+            members: SuiteMembers::new(),
+        }
+    }
+
+    fn byte(&mut self, b: u8) {
+        self.frame.push(&mut self.out, b)
+    }
+
+    fn varint(&mut self, mut n: u64) {
+        loop {
+            let low = (n & 0x7F) as u8;
+            n >>= 7;
+            if n == 0 {
+                break self.byte(low);
+            }
+            self.byte(low | 0x80);
+        }
+    }
+
+    fn usize(&mut self, n: usize) {
+        self.varint(n as u64)
+    }
+
+    // `None` is a 0 byte, `Some(n)` is a 1 byte followed by `n`.
+    fn opt_usize(&mut self, n: Option<usize>) {
+        match n {
+            None => self.byte(0),
+            Some(n) => {
+                self.byte(1);
+                self.usize(n);
+            }
+        }
+    }
+
+    fn str(&mut self, s: &str) {
+        self.usize(s.len());
+        s.bytes().for_each(|b| self.byte(b));
+    }
+
+    // Formats `v` twice: once to get the length and once to send it.
+    fn debug(&mut self, v: &dyn Debug) {
+        struct Counter(usize);
+        impl Write for Counter {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0 += s.len();
+                Ok(())
+            }
+        }
+
+        struct Bytes<'r, 'o, O: Output + ?Sized>(&'r mut BinaryReporter<'o, O>);
+        impl<O: Output + ?Sized> Write for Bytes<'_, '_, O> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                s.bytes().for_each(|b| self.0.byte(b));
+                Ok(())
+            }
+        }
+
+        // Errors from the `Debug` impl cut the text short (the same way both
+        // times); they aren't the output's fault.
+        let mut len = Counter(0);
+        let _ = write!(len, "{:?}", v);
+        self.usize(len.0);
+        let _ = write!(Bytes(self), "{:?}", v);
+    }
+
+    fn end_frame(&mut self) {
+        self.frame.finish(&mut self.out)
+    }
+
+    fn input(&mut self, input_idx: usize, input: &dyn Debug) {
+        if self.current_input != Some(input_idx) {
+            self.byte(tag::INPUT);
+            self.usize(input_idx);
+            self.debug(input);
+            self.end_frame();
+
+            self.current_input = Some(input_idx);
+        }
+    }
+
+    fn starting_top_level(&mut self, tag: u8, hint: (usize, Option<usize>)) {
+        self.byte(tag);
+        self.usize(self.next_top_level);
+        self.usize(hint.0);
+        self.opt_usize(hint.1);
+
+        self.next_top_level += 1;
+        self.current_input = None;
+    }
+}
+
+impl<'o, O, M> Reporter<M> for BinaryReporter<'o, O>
+where
+    O: Output + ?Sized,
+    M: Metric,
+{
+    type Error = O::Error;
+
+    fn top_level_benchmarks<I: Iterator<Item = &'static str> + Clone>(&mut self, names: I) {
+        self.byte(tag::TOP_LEVEL_BENCHMARKS);
+        self.usize(names.clone().count());
+        names.for_each(|n| self.str(n));
+        self.end_frame();
+
+        self.next_top_level = 0;
+    }
+
+    fn starting_single_benchmark(
+        &mut self,
+        _name: &'static str,
+        inputs_size_hint: (usize, Option<usize>),
+        iterations: usize,
+    ) {
+        self.starting_top_level(tag::STARTING_SINGLE, inputs_size_hint);
+        self.members.starting(None);
+        self.usize(iterations);
+        self.end_frame();
+    }
+
+    fn single_benchmark_iterations(&mut self, input_idx: usize, iterations: usize) {
+        self.byte(tag::SINGLE_ITERATIONS);
+        self.usize(input_idx);
+        self.usize(iterations);
+        self.end_frame();
+    }
+
+    fn single_benchmark_run(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        _iteration_idx: usize,
+        measurement: M::Unit,
+    ) {
+        self.input(input_idx, input);
+
+        self.byte(tag::SINGLE_RUN);
+        self.varint(measurement.to_raw());
+        self.end_frame();
+    }
+
+    fn ending_single_benchmark(&mut self, _name: &'static str) {
+        self.byte(tag::ENDING_SINGLE);
+        self.end_frame();
+    }
+
+    fn starting_new_benchmark_suite<I: Iterator<Item = (&'static str, usize)> + Clone>(
+        &mut self,
+        _name: &'static str,
+        inputs_size_hint: (usize, Option<usize>),
+        benchmarks: I,
+        axes: &[Axis<'_>],
+    ) {
+        self.starting_top_level(tag::STARTING_SUITE, inputs_size_hint);
+
+        let count = benchmarks.clone().count();
+        self.members.starting(Some(count));
+        self.usize(count);
+        for (name, iterations) in benchmarks {
+            self.str(name);
+            self.usize(iterations);
+        }
+
+        self.usize(axes.len());
+        for axis in axes {
+            self.str(axis.name);
+            self.usize(axis.len);
+            for idx in 0..axis.len {
+                self.debug(&axis.value(idx));
+            }
+        }
+
+        self.end_frame();
+    }
+
+    fn suite_input_axes(&mut self, input_idx: usize, _axes: &[Axis<'_>]) {
+        self.byte(tag::SUITE_INPUT_AXES);
+        self.usize(input_idx);
+        self.end_frame();
+    }
+
+    fn suite_benchmark_run(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_idx: usize,
+        benchmark_name: &'static str,
+        iteration_idx: usize,
+        num_iterations: usize,
+        measurement: M::Unit,
+    ) {
+        self.input(input_idx, input);
+        self.members.ran(benchmark_idx, benchmark_name);
+
+        self.byte(tag::SUITE_RUN);
+        self.usize(benchmark_idx);
+        self.varint(measurement.to_raw());
+        // Can be less than the count in `starting_new_benchmark_suite` for
+        // benchmarks with a budget.
+        if iteration_idx == 0 {
+            self.usize(num_iterations);
+        }
+        self.end_frame();
+    }
+
+    fn ending_benchmark_suite(&mut self, _name: &'static str) {
+        self.byte(tag::ENDING_SUITE);
+        self.end_frame();
+    }
+
+    fn verification_failed(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_name: &'static str,
+        iteration_idx: usize,
+        message: &'static str,
+    ) {
+        self.input(input_idx, input);
+
+        self.byte(tag::VERIFICATION_FAILED);
+        if let Some(idx) = self.members.failed(benchmark_name) {
+            self.usize(idx);
+        }
+        self.usize(iteration_idx);
+        self.str(message);
+        self.end_frame();
+    }
+
+    fn benchmark_panicked(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_name: &'static str,
+        message: &str,
+    ) {
+        self.input(input_idx, input);
+
+        self.byte(tag::PANICKED);
+        if let Some(idx) = self.members.failed(benchmark_name) {
+            self.usize(idx);
+        }
+        self.str(message);
+        self.end_frame();
+    }
+
+    fn ended(&mut self) {
+        self.byte(tag::ENDED);
+        self.end_frame();
+
+        self.out.flush();
+    }
+
+    fn take_error(&mut self) -> Option<Self::Error> {
+        self.out.take_error()
+    }
+}
+
+// Works out which suite member a `verification_failed`/`benchmark_panicked`
+// call (which only gets the name) is about, so that its index can be sent
+// instead of the name.
+//
+// For each input the runner goes through the members in order and every
+// member reports a run or a panic, so it's the last member heard from or the
+// one after it. (Members that share a name can get mixed up but they'd be
+// reported under the same name anyway.)
+#[derive(Debug, Clone, Copy)]
+pub(super) struct SuiteMembers {
+    // `None` for single benchmarks.
+    count: Option<usize>,
+    last: Option<(usize, &'static str)>,
+}
+
+impl SuiteMembers {
+    pub(super) const fn new() -> Self {
+        SuiteMembers {
+            count: None,
+            last: None,
+        }
+    }
+
+    pub(super) fn starting(&mut self, count: Option<usize>) {
+        *self = SuiteMembers { count, last: None };
+    }
+
+    pub(super) fn ran(&mut self, idx: usize, name: &'static str) {
+        self.last = Some((idx, name));
+    }
+
+    // The member's index; `None` for single benchmarks.
+    pub(super) fn failed(&mut self, name: &'static str) -> Option<usize> {
+        let count = self.count?;
+        let idx = match self.last {
+            Some((idx, last)) if last == name => idx,
+            Some((idx, _)) => (idx + 1) % count,
+            None => 0,
+        };
+        self.last = Some((idx, name));
+
+        Some(idx)
+    }
+}
+
+// Streaming COBS encoder; holds on to (at most) one block.
+struct Cobs {
+    block: [u8; 254],
+    len: usize,
+}
+
+impl Cobs {
+    const fn new() -> Self {
+        Cobs {
+            block: [0; 254],
+            len: 0,
+        }
+    }
+
+    fn push<O: Output + ?Sized>(&mut self, out: &mut OutputAdapter<'_, O>, byte: u8) {
+        if byte == 0 {
+            return self.emit_block(out);
+        }
+
+        self.block[self.len] = byte;
+        self.len += 1;
+
+        // Full blocks (code `0xFF`) have no implied zero after them.
+        if self.len == self.block.len() {
+            self.emit_block(out);
+        }
+    }
+
+    fn emit_block<O: Output + ?Sized>(&mut self, out: &mut OutputAdapter<'_, O>) {
+        out.bytes(&[self.len as u8 + 1]);
+        out.bytes(&self.block[..self.len]);
+        self.len = 0;
+    }
+
+    fn finish<O: Output + ?Sized>(&mut self, out: &mut OutputAdapter<'_, O>) {
+        self.emit_block(out);
+        out.bytes(&[0]);
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "std")))]
+pub use decoder::*;
+
+#[cfg(feature = "std")]
+mod decoder {
+    use std::{
+        borrow::ToOwned,
+        boxed::Box,
+        collections::HashSet,
+        fmt::{self, Debug},
+        string::String,
+        vec::Vec,
+    };
+
+    use super::tag;
+    use crate::metrics::{Metric, RawUnit};
+    use crate::runner::{Axis, AxisValues};
+    use crate::Reporter;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum DecodeError {
+        /// A frame was not valid COBS.
+        Framing,
+        /// A frame ended before all of its fields.
+        Truncated,
+        InvalidUtf8,
+        UnknownEvent(u8),
+        /// A frame referred to a benchmark or input that was not sent.
+        UnknownIndex,
+    }
+
+    impl fmt::Display for DecodeError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                DecodeError::Framing => write!(f, "invalid COBS frame"),
+                DecodeError::Truncated => write!(f, "truncated frame"),
+                DecodeError::InvalidUtf8 => write!(f, "string is not valid UTF-8"),
+                DecodeError::UnknownEvent(t) => write!(f, "unknown event tag: {}", t),
+                DecodeError::UnknownIndex => write!(f, "unknown benchmark or input index"),
+            }
+        }
+    }
+
+    impl std::error::Error for DecodeError {}
+
+    /// Turns the output of a [`BinaryReporter`](super::BinaryReporter) back
+    /// into calls on a [`Reporter`].
+    ///
+    /// Bytes can be fed in as they arrive; [`Reporter`] calls are made as
+    /// soon as each frame is complete.
+    ///
+    /// Note: [`Reporter`] wants `&'static str`s for benchmark names and
+    /// failure messages so these are leaked (once per distinct string).
+    #[derive(Default)]
+    pub struct BinaryDecoder {
+        frame: Vec<u8>,
+        interned: HashSet<&'static str>,
+
+        top_level: Vec<&'static str>,
+        current_top_level: Option<&'static str>,
+        in_suite: bool,
+        suite_members: Vec<(&'static str, usize)>,
+        axes: Vec<(&'static str, AxisText)>,
+        input: Option<(usize, String)>,
+        // Run frames don't carry iteration indexes; we count them instead.
+        last_benchmark_idx: Option<usize>,
+        next_iteration_idx: usize,
+        // From the first run of the current (suite member, input) pair.
+        suite_iterations: usize,
+
+        // `DefmtReporter` lines are turned into frames; some frames take
+        // several lines.
+        defmt_frame: Vec<u8>,
+        defmt_lines_left: usize,
+    }
+
+    struct AxisText(Vec<String>);
+    impl AxisValues for AxisText {
+        fn fmt_value(&self, idx: usize, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.0.get(idx).map(|s| &**s).unwrap_or(""))
+        }
+    }
+
+    // `Debug` text, as sent.
+    struct Text<'a>(&'a str);
+    impl Debug for Text<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+
+    struct Fields<'a>(&'a [u8]);
+    impl<'a> Fields<'a> {
+        fn byte(&mut self) -> Result<u8, DecodeError> {
+            let (b, rest) = self.0.split_first().ok_or(DecodeError::Truncated)?;
+            self.0 = rest;
+            Ok(*b)
+        }
+
+        fn varint(&mut self) -> Result<u64, DecodeError> {
+            let mut n = 0;
+            for shift in (0..64).step_by(7) {
+                let b = self.byte()?;
+                n |= ((b & 0x7F) as u64) << shift;
+                if b & 0x80 == 0 {
+                    return Ok(n);
+                }
+            }
+
+            Err(DecodeError::Framing)
+        }
+
+        fn usize(&mut self) -> Result<usize, DecodeError> {
+            self.varint().map(|n| n as usize)
+        }
+
+        fn opt_usize(&mut self) -> Result<Option<usize>, DecodeError> {
+            match self.byte()? {
+                0 => Ok(None),
+                1 => self.usize().map(Some),
+                _ => Err(DecodeError::Framing),
+            }
+        }
+
+        fn str(&mut self) -> Result<&'a str, DecodeError> {
+            let len = self.usize()?;
+            if len > self.0.len() {
+                return Err(DecodeError::Truncated);
+            }
+
+            let (s, rest) = self.0.split_at(len);
+            self.0 = rest;
+            std::str::from_utf8(s).map_err(|_| DecodeError::InvalidUtf8)
+        }
+    }
+
+    // Builds (unencoded) frames; the counterpart to `Fields`.
+    struct FrameBuilder<'a>(&'a mut Vec<u8>);
+    impl FrameBuilder<'_> {
+        fn byte(&mut self, b: u8) {
+            self.0.push(b)
+        }
+
+        fn varint(&mut self, mut n: u64) {
+            loop {
+                let low = (n & 0x7F) as u8;
+                n >>= 7;
+                if n == 0 {
+                    break self.byte(low);
+                }
+                self.byte(low | 0x80);
+            }
+        }
+
+        fn usize(&mut self, n: usize) {
+            self.varint(n as u64)
+        }
+
+        fn opt_usize(&mut self, n: Option<usize>) {
+            match n {
+                None => self.byte(0),
+                Some(n) => {
+                    self.byte(1);
+                    self.usize(n);
+                }
+            }
+        }
+
+        fn str(&mut self, s: &str) {
+            self.usize(s.len());
+            self.0.extend_from_slice(s.as_bytes());
+        }
+    }
+
+    // The space separated arguments of a `DefmtReporter` line.
+    struct Words<'a>(&'a str);
+    impl<'a> Words<'a> {
+        fn word(&mut self) -> Result<&'a str, DecodeError> {
+            if self.0.is_empty() {
+                return Err(DecodeError::Truncated);
+            }
+
+            let (word, rest) = self.0.split_once(' ').unwrap_or((self.0, ""));
+            self.0 = rest;
+            Ok(word)
+        }
+
+        fn varint(&mut self) -> Result<u64, DecodeError> {
+            self.word()?.parse().map_err(|_| DecodeError::Framing)
+        }
+
+        fn usize(&mut self) -> Result<usize, DecodeError> {
+            self.word()?.parse().map_err(|_| DecodeError::Framing)
+        }
+
+        fn opt_usize(&mut self) -> Result<Option<usize>, DecodeError> {
+            match self.word()? {
+                "-" => Ok(None),
+                w => w.parse().map(Some).map_err(|_| DecodeError::Framing),
+            }
+        }
+
+        fn rest(&mut self) -> &'a str {
+            core::mem::take(&mut self.0)
+        }
+    }
+
+    fn cobs_decode(frame: &[u8]) -> Result<Vec<u8>, DecodeError> {
+        let mut out = Vec::with_capacity(frame.len());
+        let mut i = 0;
+        while i < frame.len() {
+            let code = frame[i] as usize;
+            let block = frame.get(i + 1..i + code).ok_or(DecodeError::Framing)?;
+            out.extend_from_slice(block);
+            i += code;
+
+            if code != 0xFF && i < frame.len() {
+                out.push(0);
+            }
+        }
+
+        Ok(out)
+    }
+
+    impl BinaryDecoder {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Decodes `bytes`, calling into `reporter` for every complete frame.
+        ///
+        /// Frames that fail to decode are skipped; the first error is
+        /// returned once all of `bytes` has been consumed.
+        pub fn feed<M, R>(&mut self, bytes: &[u8], reporter: &mut R) -> Result<(), DecodeError>
+        where
+            M: Metric,
+            R: Reporter<M>,
+        {
+            let mut res = Ok(());
+            for &b in bytes {
+                if b != 0 {
+                    self.frame.push(b);
+                    continue;
+                }
+
+                let frame = cobs_decode(&self.frame);
+                self.frame.clear();
+
+                let event = frame.and_then(|f| self.event(&f, reporter));
+                if res.is_ok() {
+                    res = event;
+                }
+            }
+
+            res
+        }
+
+        /// Decodes a line written by a
+        /// [`FrameLines`](crate::reporters::FrameLines); lines that don't
+        /// contain frame bytes are ignored.
+        pub fn feed_frame_line<M, R>(
+            &mut self,
+            line: &str,
+            reporter: &mut R,
+        ) -> Result<(), DecodeError>
+        where
+            M: Metric,
+            R: Reporter<M>,
+        {
+            // i.e. `ubench-frame [6, 1, 2, 0]`, possibly after other text.
+            const PREFIX: &str = "ubench-frame [";
+
+            let bytes = match line.find(PREFIX) {
+                Some(start) => &line[start + PREFIX.len()..],
+                None => return Ok(()),
+            };
+            let bytes = bytes.split(']').next().ok_or(DecodeError::Framing)?;
+            let bytes = bytes
+                .split(',')
+                .map(str::trim)
+                .filter(|b| !b.is_empty())
+                .map(|b| b.parse::<u8>().map_err(|_| DecodeError::Framing))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            self.feed(&bytes, reporter)
+        }
+
+        /// Decodes a line of `defmt-print` (or `probe-rs`) output logged by a
+        /// [`DefmtReporter`](crate::reporters::DefmtReporter) on the device;
+        /// other lines are ignored.
+        ///
+        /// The lines are turned into the frames a
+        /// [`BinaryReporter`](super::BinaryReporter) would have sent so
+        /// [`Reporter`] calls are made as soon as each call's (last) line
+        /// arrives.
+        pub fn feed_defmt_line<M, R>(
+            &mut self,
+            line: &str,
+            reporter: &mut R,
+        ) -> Result<(), DecodeError>
+        where
+            M: Metric,
+            R: Reporter<M>,
+        {
+            // i.e. `ubench-run 1234`, possibly after a timestamp, log level,
+            // etc.
+            const PREFIX: &str = "ubench-";
+
+            let line = match line.find(PREFIX) {
+                Some(start) => line[start + PREFIX.len()..].trim_end_matches(&['\r', '\n'][..]),
+                None => return Ok(()),
+            };
+            let (event, args) = line.split_once(' ').unwrap_or((line, ""));
+
+            match self.defmt_line(event, Words(args)) {
+                Ok(true) if self.defmt_lines_left == 0 => {
+                    let frame = core::mem::take(&mut self.defmt_frame);
+                    self.event(&frame, reporter)
+                }
+                Ok(_) => Ok(()),
+                Err(e) => {
+                    self.defmt_frame.clear();
+                    self.defmt_lines_left = 0;
+                    Err(e)
+                }
+            }
+        }
+
+        // Adds the line to `defmt_frame`; returns `false` for lines that
+        // aren't `DefmtReporter`'s. Keep in sync with `DefmtReporter`.
+        fn defmt_line(&mut self, event: &str, mut w: Words<'_>) -> Result<bool, DecodeError> {
+            let continues = matches!(
+                event,
+                "benchmark" | "member" | "suite-axes" | "axis" | "value"
+            );
+            if continues {
+                if self.defmt_lines_left == 0 {
+                    return Err(DecodeError::Framing);
+                }
+                self.defmt_lines_left -= 1;
+            } else if self.defmt_lines_left != 0 {
+                // The rest of the last call's lines went missing.
+                return Err(DecodeError::Truncated);
+            } else {
+                self.defmt_frame.clear();
+            }
+
+            let f = &mut FrameBuilder(&mut self.defmt_frame);
+            match event {
+                "benchmarks" => {
+                    f.byte(tag::TOP_LEVEL_BENCHMARKS);
+                    let count = w.usize()?;
+                    f.usize(count);
+                    // (Then the metric's unit, for people reading the logs.)
+                    self.defmt_lines_left = count;
+                }
+                "benchmark" => f.str(w.rest()),
+                "single" => {
+                    f.byte(tag::STARTING_SINGLE);
+                    f.usize(w.usize()?);
+                    f.usize(w.usize()?);
+                    f.opt_usize(w.opt_usize()?);
+                    f.usize(w.usize()?);
+                }
+                "input" => {
+                    f.byte(tag::INPUT);
+                    f.usize(w.usize()?);
+                    f.str(w.rest());
+                }
+                "iterations" => {
+                    f.byte(tag::SINGLE_ITERATIONS);
+                    f.usize(w.usize()?);
+                    f.usize(w.usize()?);
+                }
+                "run" => {
+                    f.byte(tag::SINGLE_RUN);
+                    f.varint(w.varint()?);
+                }
+                "end-single" => f.byte(tag::ENDING_SINGLE),
+                "suite" => {
+                    f.byte(tag::STARTING_SUITE);
+                    f.usize(w.usize()?);
+                    f.usize(w.usize()?);
+                    f.opt_usize(w.opt_usize()?);
+                    let members = w.usize()?;
+                    f.usize(members);
+                    // The members and then the number of axes:
+                    self.defmt_lines_left = members + 1;
+                }
+                "member" => {
+                    let iterations = w.usize()?;
+                    f.str(w.rest());
+                    f.usize(iterations);
+                }
+                "suite-axes" => {
+                    let count = w.usize()?;
+                    f.usize(count);
+                    self.defmt_lines_left += count;
+                }
+                "axis" => {
+                    let len = w.usize()?;
+                    f.str(w.rest());
+                    f.usize(len);
+                    self.defmt_lines_left += len;
+                }
+                "value" => f.str(w.rest()),
+                "input-axes" => {
+                    f.byte(tag::SUITE_INPUT_AXES);
+                    f.usize(w.usize()?);
+                }
+                "suite-run" => {
+                    f.byte(tag::SUITE_RUN);
+                    f.usize(w.usize()?);
+                    f.varint(w.varint()?);
+                    // Only the first run has the iteration count.
+                    if !w.0.is_empty() {
+                        f.usize(w.usize()?);
+                    }
+                }
+                "end-suite" => f.byte(tag::ENDING_SUITE),
+                "failed" => {
+                    f.byte(tag::VERIFICATION_FAILED);
+                    let it_idx = w.usize()?;
+                    if let Some(member) = w.opt_usize()? {
+                        f.usize(member);
+                    }
+                    f.usize(it_idx);
+                    f.str(w.rest());
+                }
+                "panicked" => {
+                    f.byte(tag::PANICKED);
+                    if let Some(member) = w.opt_usize()? {
+                        f.usize(member);
+                    }
+                    f.str(w.rest());
+                }
+                "ended" => f.byte(tag::ENDED),
+                _ => return Ok(false),
+            }
+
+            Ok(true)
+        }
+
+        fn intern(&mut self, s: &str) -> &'static str {
+            if let Some(s) = self.interned.get(s) {
+                return s;
+            }
+
+            let s = Box::leak(s.to_owned().into_boxed_str());
+            self.interned.insert(s);
+            s
+        }
+
+        fn input(&self) -> Result<(usize, Text<'_>), DecodeError> {
+            match self.input {
+                Some((idx, ref text)) => Ok((idx, Text(text))),
+                None => Err(DecodeError::UnknownIndex),
+            }
+        }
+
+        fn next_iteration(&mut self, benchmark_idx: Option<usize>) -> usize {
+            if benchmark_idx != self.last_benchmark_idx {
+                self.last_benchmark_idx = benchmark_idx;
+                self.next_iteration_idx = 0;
+            }
+
+            self.next_iteration_idx += 1;
+            self.next_iteration_idx - 1
+        }
+
+        fn with_axes(&self, f: impl FnOnce(&[Axis<'_>])) {
+            let axes: Vec<_> = self
+                .axes
+                .iter()
+                .map(|(name, values)| Axis::new(name, values.0.len(), values))
+                .collect();
+            f(&axes)
+        }
+
+        fn event<M, R>(&mut self, frame: &[u8], r: &mut R) -> Result<(), DecodeError>
+        where
+            M: Metric,
+            R: Reporter<M>,
+        {
+            let mut fields = Fields(frame);
+            let f = &mut fields;
+            let unit = |raw| <M::Unit as RawUnit>::from_raw(raw);
+
+            match f.byte()? {
+                tag::TOP_LEVEL_BENCHMARKS => {
+                    let names = (0..f.usize()?)
+                        .map(|_| Ok(self.intern(f.str()?)))
+                        .collect::<Result<_, _>>()?;
+                    self.top_level = names;
+                    r.top_level_benchmarks(self.top_level.iter().copied());
+                }
+                tag::STARTING_SINGLE => {
+                    let name = self.starting_top_level(f)?;
+                    let hint = (f.usize()?, f.opt_usize()?);
+                    self.in_suite = false;
+                    r.starting_single_benchmark(name, hint, f.usize()?);
+                }
+                tag::INPUT => {
+                    let idx = f.usize()?;
+                    self.input = Some((idx, f.str()?.to_owned()));
+                    self.last_benchmark_idx = None;
+                    self.next_iteration_idx = 0;
+                }
+                tag::SINGLE_ITERATIONS => {
+                    let (idx, iterations) = (f.usize()?, f.usize()?);
+                    r.single_benchmark_iterations(idx, iterations);
+                }
+                tag::SINGLE_RUN => {
+                    let raw = f.varint()?;
+                    let it_idx = self.next_iteration(None);
+                    let (idx, input) = self.input()?;
+                    r.single_benchmark_run(idx, &input, it_idx, unit(raw));
+                }
+                tag::ENDING_SINGLE => {
+                    let name = self.current_top_level.ok_or(DecodeError::UnknownIndex)?;
+                    r.ending_single_benchmark(name);
+                }
+                tag::STARTING_SUITE => {
+                    let name = self.starting_top_level(f)?;
+                    let hint = (f.usize()?, f.opt_usize()?);
+
+                    self.in_suite = true;
+                    self.suite_members.clear();
+                    for _ in 0..f.usize()? {
+                        let member = (self.intern(f.str()?), f.usize()?);
+                        self.suite_members.push(member);
+                    }
+
+                    self.axes.clear();
+                    for _ in 0..f.usize()? {
+                        let name = self.intern(f.str()?);
+                        let values = (0..f.usize()?)
+                            .map(|_| f.str().map(ToOwned::to_owned))
+                            .collect::<Result<_, _>>()?;
+                        self.axes.push((name, AxisText(values)));
+                    }
+
+                    let members = self.suite_members.iter().copied();
+                    self.with_axes(|axes| {
+                        r.starting_new_benchmark_suite(name, hint, members, axes)
+                    });
+                }
+                tag::SUITE_INPUT_AXES => {
+                    let idx = f.usize()?;
+                    self.with_axes(|axes| r.suite_input_axes(idx, axes));
+                }
+                tag::SUITE_RUN => {
+                    let (b_idx, raw) = (f.usize()?, f.varint()?);
+                    let name = self
+                        .suite_members
+                        .get(b_idx)
+                        .ok_or(DecodeError::UnknownIndex)?
+                        .0;
+                    let it_idx = self.next_iteration(Some(b_idx));
+                    if it_idx == 0 {
+                        self.suite_iterations = f.usize()?;
+                    }
+                    let iterations = self.suite_iterations;
+                    let (idx, input) = self.input()?;
+                    r.suite_benchmark_run(idx, &input, b_idx, name, it_idx, iterations, unit(raw));
+                }
+                tag::ENDING_SUITE => {
+                    let name = self.current_top_level.ok_or(DecodeError::UnknownIndex)?;
+                    r.ending_benchmark_suite(name);
+                }
+                tag::VERIFICATION_FAILED => {
+                    let name = self.failed_benchmark(f)?;
+                    let it_idx = f.usize()?;
+                    let message = self.intern(f.str()?);
+                    let (idx, input) = self.input()?;
+                    r.verification_failed(idx, &input, name, it_idx, message);
+                }
+                tag::PANICKED => {
+                    let name = self.failed_benchmark(f)?;
+                    let message = f.str()?;
+                    let (idx, input) = self.input()?;
+                    r.benchmark_panicked(idx, &input, name, message);
+                }
+                tag::ENDED => r.ended(),
+                other => return Err(DecodeError::UnknownEvent(other)),
+            }
+
+            Ok(())
+        }
+
+        // The benchmark a failure frame is about: a suite member's index or,
+        // in single benchmarks, nothing.
+        fn failed_benchmark(&self, f: &mut Fields<'_>) -> Result<&'static str, DecodeError> {
+            let name = if self.in_suite {
+                self.suite_members.get(f.usize()?).map(|m| m.0)
+            } else {
+                self.current_top_level
+            };
+            name.ok_or(DecodeError::UnknownIndex)
+        }
+
+        fn starting_top_level(&mut self, f: &mut Fields<'_>) -> Result<&'static str, DecodeError> {
+            let name = *self
+                .top_level
+                .get(f.usize()?)
+                .ok_or(DecodeError::UnknownIndex)?;
+            self.current_top_level = Some(name);
+            self.input = None;
+
+            Ok(name)
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use core::future::Future;
+use core::pin::pin;
+use core::ptr;
+use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+use embedded_io::{ErrorType, Write};
+
+/// Makes an `embedded_io_async::Write` usable as an `embedded_io::Write` (and
+/// so with `new_with_embedded_io`) by polling its futures until they finish.
+///
+/// There's no executor here: the futures are polled in a loop with a waker
+/// that does nothing. This is fine for drivers whose futures check the
+/// hardware each time they're polled, but not for ones that wait on
+/// something else getting to run (i.e. another task on the same executor).
+pub struct Blocking<W>(pub W);
+
+impl<W: ErrorType> ErrorType for Blocking<W> {
+    type Error = W::Error;
+}
+
+impl<W: embedded_io_async::Write> Write for Blocking<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, W::Error> {
+        block_on(self.0.write(buf))
+    }
+
+    fn flush(&mut self) -> Result<(), W::Error> {
+        block_on(self.0.flush())
+    }
+}
+
+fn block_on<F: Future>(fut: F) -> F::Output {
+    const VTABLE: RawWakerVTable = RawWakerVTable::new(
+        |_| RawWaker::new(ptr::null(), &VTABLE),
+        |_| {},
+        |_| {},
+        |_| {},
+    );
+
+    // Safety: the vtable's functions don't touch the data pointer.
+    let waker = unsafe { Waker::from_raw(RawWaker::new(ptr::null(), &VTABLE)) };
+    let mut cx = Context::from_waker(&waker);
+    let mut fut = pin!(fut);
+    loop {
+        if let Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+            return out;
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use super::io::Output;
+
+/// Where a [`BufferedOutput`] sends what's been queued up.
+///
+/// Implemented for `embedded_hal::serial::Write<u8>` (bytes go in until the
+/// UART would block); implement it for a DMA channel or for the queue an
+/// interrupt handler drains to move sending off of the CPU entirely.
+pub trait Sink {
+    type Error;
+
+    /// Takes as much of `queued` as it can _without blocking_ and returns how
+    /// many bytes it took (0 if it's busy).
+    ///
+    /// `queued` stays put in the buffer until it's been taken, so a DMA
+    /// transfer can be started from it directly: return 0 while the transfer
+    /// is running and the transfer's length once it's done.
+    fn send(&mut self, queued: &[u8]) -> Result<usize, Self::Error>;
+
+    /// Called once everything queued has been sent.
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "embedded-hal")]
+#[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "embedded-hal")))]
+impl<W: embedded_hal::serial::Write<u8>> Sink for W {
+    type Error = W::Error;
+
+    fn send(&mut self, queued: &[u8]) -> Result<usize, W::Error> {
+        for (i, b) in queued.iter().enumerate() {
+            match self.write(*b) {
+                Ok(()) => {}
+                Err(nb::Error::WouldBlock) => return Ok(i),
+                Err(nb::Error::Other(e)) => return Err(e),
+            }
+        }
+        Ok(queued.len())
+    }
+
+    fn flush(&mut self) -> Result<(), W::Error> {
+        nb::block!(W::flush(self))
+    }
+}
+
+/// An output that queues what's written to it in a ring buffer of `N` bytes
+/// and hands it to a [`Sink`] a bit at a time, instead of blocking on every
+/// byte.
+///
+/// Reporters only write between measurements so this only ever calls into
+/// the sink between measurements; after each write it sends what the sink
+/// will take without blocking (i.e. whatever fits in the UART's FIFO) and the
+/// rest is sent during later writes. It only blocks when the buffer is full
+/// or when it's flushed (reporters flush at the end of the run;
+/// [`ProgressReporter`](super::ProgressReporter) also flushes after every
+/// update) so make `N` large enough to hold what's reported between flushes
+/// to never block mid-run.
+///
+/// ```rust,ignore
+/// let mut out = BufferedOutput::<_, 2048>::new(uart);
+/// BenchmarkRunner::new()
+///     .add(/* ... */)
+///     .run(&mut metric, &mut BasicReporter::new(&mut out))?;
+/// ```
+pub struct BufferedOutput<S, const N: usize> {
+    sink: S,
+    buf: [u8; N],
+    // Queued bytes are `buf[start..][..len]`, wrapping around.
+    start: usize,
+    len: usize,
+}
+
+impl<S: Sink, const N: usize> BufferedOutput<S, N> {
+    pub const fn new(sink: S) -> Self {
+        assert!(N > 0, "the buffer can't be empty");
+        BufferedOutput {
+            sink,
+            buf: [0; N],
+            start: 0,
+            len: 0,
+        }
+    }
+
+    /// Gives back the sink; anything still queued is dropped.
+    pub fn into_inner(self) -> S {
+        self.sink
+    }
+
+    pub fn sink_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    /// The number of bytes waiting to be sent.
+    pub fn queued(&self) -> usize {
+        self.len
+    }
+
+    /// Sends what the sink will take without blocking.
+    pub fn poll(&mut self) -> Result<(), S::Error> {
+        while self.len != 0 {
+            let end = (self.start + self.len).min(N);
+            let sent = self.sink.send(&self.buf[self.start..end])?;
+            if sent == 0 {
+                break;
+            }
+
+            self.start = (self.start + sent) % N;
+            self.len -= sent;
+        }
+
+        Ok(())
+    }
+
+    /// Sends everything that's queued, waiting on the sink as needed.
+    pub fn drain(&mut self) -> Result<(), S::Error> {
+        while self.len != 0 {
+            self.poll()?;
+        }
+
+        Ok(())
+    }
+
+    // Copies as much of `bytes` as fits; returns how much that was.
+    fn push(&mut self, bytes: &[u8]) -> usize {
+        let mut pushed = 0;
+        while pushed < bytes.len() && self.len < N {
+            let end = (self.start + self.len) % N;
+            let room = if end < self.start { self.start } else { N } - end;
+            let n = room.min(bytes.len() - pushed);
+
+            self.buf[end..][..n].copy_from_slice(&bytes[pushed..][..n]);
+            self.len += n;
+            pushed += n;
+        }
+
+        pushed
+    }
+}
+
+impl<S: Sink, const N: usize> Output for BufferedOutput<S, N> {
+    type Error = S::Error;
+
+    fn output_str(&mut self, s: &str) -> Result<(), S::Error> {
+        self.output_bytes(s.as_bytes())
+    }
+
+    fn output_bytes(&mut self, mut bytes: &[u8]) -> Result<(), S::Error> {
+        loop {
+            bytes = &bytes[self.push(bytes)..];
+            if bytes.is_empty() {
+                break self.poll();
+            }
+
+            // Full; wait for some room:
+            self.poll()?;
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), S::Error> {
+        self.drain()?;
+        self.sink.flush()
+    }
+}
@@ -0,0 +1,227 @@
+use core::convert::Infallible;
+use core::fmt::Debug;
+
+use defmt::{println, Debug2Format};
+
+use super::binary::SuiteMembers;
+use super::Reporter;
+use crate::metrics::{Metric, RawUnit};
+use crate::runner::Axis;
+
+/// A [`Reporter`] that logs every call with `defmt::println!` (i.e. over RTT
+/// with `probe-rs`), for boards that already use `defmt`.
+///
+/// Each call is one or more `ubench-…` log lines. The format strings are
+/// interned by `defmt` so only the arguments go over the wire: measurements
+/// (as [`RawUnit`]s) and indexes are integers and, like
+/// [`BinaryReporter`](super::BinaryReporter), benchmark names and inputs (as
+/// `Debug` text) are sent once and referred to by index after that.
+///
+/// On the host, hand the lines `defmt-print`/`probe-rs` print to a
+/// [`BinaryDecoder`](super::BinaryDecoder) with
+/// [`feed_defmt_line`](super::BinaryDecoder::feed_defmt_line) to get the
+/// calls back. Lines have to end with the log message (no trailing location
+/// info) and inputs' `Debug` text has to fit on one line.
+pub struct DefmtReporter {
+    // Index (in `top_level_benchmarks` order) of the next top level benchmark.
+    next_top_level: usize,
+    // The last input sent for the current top level benchmark.
+    current_input: Option<usize>,
+    members: SuiteMembers,
+}
+
+// Keep the lines in sync with `BinaryDecoder::feed_defmt_line`.
+impl DefmtReporter {
+    pub const fn new() -> Self {
+        DefmtReporter {
+            next_top_level: 0,
+            current_input: None,
+            members: SuiteMembers::new(),
+        }
+    }
+
+    fn input(&mut self, input_idx: usize, input: &dyn Debug) {
+        if self.current_input != Some(input_idx) {
+            println!("ubench-input {=usize} {}", input_idx, Debug2Format(input));
+            self.current_input = Some(input_idx);
+        }
+    }
+
+    // Returns the index of the top level benchmark that's starting.
+    fn starting_top_level(&mut self) -> usize {
+        self.next_top_level += 1;
+        self.current_input = None;
+
+        self.next_top_level - 1
+    }
+}
+
+impl Default for DefmtReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M> Reporter<M> for DefmtReporter
+where
+    M: Metric,
+{
+    type Error = Infallible;
+
+    fn top_level_benchmarks<I: Iterator<Item = &'static str> + Clone>(&mut self, names: I) {
+        println!(
+            "ubench-benchmarks {=usize} {=str}",
+            names.clone().count(),
+            M::UNIT_NAME
+        );
+        names.for_each(|n| println!("ubench-benchmark {=str}", n));
+
+        self.next_top_level = 0;
+    }
+
+    fn starting_single_benchmark(
+        &mut self,
+        _name: &'static str,
+        inputs_size_hint: (usize, Option<usize>),
+        iterations: usize,
+    ) {
+        let idx = self.starting_top_level();
+        self.members.starting(None);
+        match inputs_size_hint {
+            (low, Some(high)) => println!(
+                "ubench-single {=usize} {=usize} {=usize} {=usize}",
+                idx, low, high, iterations
+            ),
+            (low, None) => println!(
+                "ubench-single {=usize} {=usize} - {=usize}",
+                idx, low, iterations
+            ),
+        }
+    }
+
+    fn single_benchmark_iterations(&mut self, input_idx: usize, iterations: usize) {
+        println!("ubench-iterations {=usize} {=usize}", input_idx, iterations);
+    }
+
+    fn single_benchmark_run(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        _iteration_idx: usize,
+        measurement: M::Unit,
+    ) {
+        self.input(input_idx, input);
+        println!("ubench-run {=u64}", measurement.to_raw());
+    }
+
+    fn ending_single_benchmark(&mut self, _name: &'static str) {
+        println!("ubench-end-single");
+    }
+
+    fn starting_new_benchmark_suite<I: Iterator<Item = (&'static str, usize)> + Clone>(
+        &mut self,
+        _name: &'static str,
+        inputs_size_hint: (usize, Option<usize>),
+        benchmarks: I,
+        axes: &[Axis<'_>],
+    ) {
+        let idx = self.starting_top_level();
+        let members = benchmarks.clone().count();
+        self.members.starting(Some(members));
+        match inputs_size_hint {
+            (low, Some(high)) => println!(
+                "ubench-suite {=usize} {=usize} {=usize} {=usize}",
+                idx, low, high, members
+            ),
+            (low, None) => println!(
+                "ubench-suite {=usize} {=usize} - {=usize}",
+                idx, low, members
+            ),
+        }
+        for (name, iterations) in benchmarks {
+            println!("ubench-member {=usize} {=str}", iterations, name);
+        }
+
+        println!("ubench-suite-axes {=usize}", axes.len());
+        for axis in axes {
+            println!("ubench-axis {=usize} {=str}", axis.len, axis.name);
+            for idx in 0..axis.len {
+                println!("ubench-value {}", Debug2Format(&axis.value(idx)));
+            }
+        }
+    }
+
+    fn suite_input_axes(&mut self, input_idx: usize, _axes: &[Axis<'_>]) {
+        println!("ubench-input-axes {=usize}", input_idx);
+    }
+
+    fn suite_benchmark_run(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_idx: usize,
+        benchmark_name: &'static str,
+        iteration_idx: usize,
+        num_iterations: usize,
+        measurement: M::Unit,
+    ) {
+        self.input(input_idx, input);
+        self.members.ran(benchmark_idx, benchmark_name);
+        // Like `BinaryReporter`, the iteration count goes with the first run.
+        if iteration_idx == 0 {
+            println!(
+                "ubench-suite-run {=usize} {=u64} {=usize}",
+                benchmark_idx,
+                measurement.to_raw(),
+                num_iterations
+            );
+        } else {
+            println!(
+                "ubench-suite-run {=usize} {=u64}",
+                benchmark_idx,
+                measurement.to_raw()
+            );
+        }
+    }
+
+    fn ending_benchmark_suite(&mut self, _name: &'static str) {
+        println!("ubench-end-suite");
+    }
+
+    fn verification_failed(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_name: &'static str,
+        iteration_idx: usize,
+        message: &'static str,
+    ) {
+        self.input(input_idx, input);
+        // Like `BinaryReporter`, suite members are referred to by index.
+        match self.members.failed(benchmark_name) {
+            Some(idx) => println!(
+                "ubench-failed {=usize} {=usize} {=str}",
+                iteration_idx, idx, message
+            ),
+            None => println!("ubench-failed {=usize} - {=str}", iteration_idx, message),
+        }
+    }
+
+    fn benchmark_panicked(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_name: &'static str,
+        message: &str,
+    ) {
+        self.input(input_idx, input);
+        match self.members.failed(benchmark_name) {
+            Some(idx) => println!("ubench-panicked {=usize} {=str}", idx, message),
+            None => println!("ubench-panicked - {=str}", message),
+        }
+    }
+
+    fn ended(&mut self) {
+        println!("ubench-ended");
+    }
+}
@@ -0,0 +1,73 @@
+use core::fmt;
+
+use super::io::Output;
+
+// (Array lengths can't use `Self::CHUNK_SIZE`; the type is generic.)
+const CHUNK_SIZE: usize = 32;
+
+/// An [`Output`] that writes the bytes written to it as text lines (i.e.
+/// `ubench-frame [6, 1, 2, 0]`), a few at a time, for sending
+/// [`BinaryReporter`](super::BinaryReporter) frames over a link that's also
+/// used for text (panic messages, logs, etc.).
+///
+/// On the host, hand the lines to a [`BinaryDecoder`](super::BinaryDecoder)
+/// with [`feed_frame_line`](super::BinaryDecoder::feed_frame_line); other
+/// lines are skipped.
+pub struct FrameLines<W: fmt::Write> {
+    out: W,
+    buf: [u8; CHUNK_SIZE],
+    len: usize,
+}
+
+impl<W: fmt::Write> FrameLines<W> {
+    /// Bytes are written out once this many are buffered or a frame ends.
+    pub const CHUNK_SIZE: usize = CHUNK_SIZE;
+
+    pub fn new(out: W) -> Self {
+        FrameLines {
+            out,
+            buf: [0; CHUNK_SIZE],
+            len: 0,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    fn line(&mut self) -> fmt::Result {
+        if self.len == 0 {
+            return Ok(());
+        }
+
+        // Keep in sync with `BinaryDecoder::feed_frame_line`.
+        let res = writeln!(self.out, "ubench-frame {:?}", &self.buf[..self.len]);
+        self.len = 0;
+        res
+    }
+}
+
+impl<W: fmt::Write> Output for FrameLines<W> {
+    type Error = fmt::Error;
+
+    fn output_str(&mut self, s: &str) -> fmt::Result {
+        self.output_bytes(s.as_bytes())
+    }
+
+    fn output_bytes(&mut self, bytes: &[u8]) -> fmt::Result {
+        for &b in bytes {
+            self.buf[self.len] = b;
+            self.len += 1;
+
+            // Frames end with a zero byte; send them right away.
+            if b == 0 || self.len == Self::CHUNK_SIZE {
+                self.line()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> fmt::Result {
+        self.line()
+    }
+}
@@ -0,0 +1,549 @@
+use core::fmt::{self, Debug, Write};
+use std::{format, string::String, vec, vec::Vec};
+
+use super::io::{Output, OutputAdapter, Void};
+use super::Reporter;
+use crate::metrics::{Metric, MetricFmtAdapter, RawUnit};
+use crate::runner::Axis;
+
+const WIDTH: u64 = 720;
+const HEIGHT: u64 = 320;
+const BOX_WIDTH: u64 = 240;
+// Left (room for tick labels), right, top, bottom (room for input labels).
+const MARGIN: (u64, u64, u64, u64) = (80, 16, 16, 48);
+const PALETTE: [&str; 8] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#9c755f",
+];
+
+const STYLE: &str = "\
+body{font-family:system-ui,sans-serif;margin:2em auto;max-width:60em;color:#222}\
+h2{border-bottom:1px solid #ccc}\
+svg{font-size:11px;overflow:visible}\
+.axis{stroke:#888}.grid{stroke:#eee}\
+.boxes{display:flex;flex-wrap:wrap;gap:1em}\
+.boxes figure{margin:0}\
+figcaption{font-family:monospace;text-align:center}\
+table{border-collapse:collapse}td,th{padding:.25em .75em;border:1px solid #ddd}\
+td{text-align:right;font-family:monospace}td:first-child{text-align:left}\
+.bad{color:#c00}\
+.legend span{margin-right:1em}";
+
+// A top level benchmark; single benchmarks have one member with the same name.
+struct Section {
+    name: &'static str,
+    members: Vec<&'static str>,
+    inputs: Vec<String>,
+    // `[member][input]` -> raw samples.
+    samples: Vec<Vec<Vec<u64>>>,
+    // `(member, input, message)`
+    problems: Vec<(usize, usize, String)>,
+}
+
+impl Section {
+    fn new(name: &'static str, members: Vec<&'static str>) -> Self {
+        Section {
+            name,
+            samples: members.iter().map(|_| Vec::new()).collect(),
+            members,
+            inputs: Vec::new(),
+            problems: Vec::new(),
+        }
+    }
+
+    fn input(&mut self, input_idx: usize, input: &dyn Debug) {
+        while self.inputs.len() <= input_idx {
+            self.inputs.push(String::new());
+        }
+        if self.inputs[input_idx].is_empty() {
+            // (Not `format!`: that panics if the `Debug` impl fails.)
+            let _ = write!(self.inputs[input_idx], "{:?}", input);
+        }
+    }
+
+    fn samples_mut(&mut self, member: usize, input_idx: usize) -> &mut Vec<u64> {
+        let samples = &mut self.samples[member];
+        if samples.len() <= input_idx {
+            samples.resize(input_idx + 1, Vec::new());
+        }
+        &mut samples[input_idx]
+    }
+
+    fn samples(&self, member: usize, input_idx: usize) -> &[u64] {
+        self.samples[member]
+            .get(input_idx)
+            .map(|s| &**s)
+            .unwrap_or_default()
+    }
+
+    fn member_of(&self, name: &str) -> usize {
+        self.members.iter().position(|m| *m == name).unwrap_or(0)
+    }
+}
+
+// Summary of a set of samples, in raw units.
+struct Summary {
+    min: u64,
+    q1: u64,
+    median: u64,
+    q3: u64,
+    max: u64,
+    avg: u64,
+}
+
+impl Summary {
+    fn of(samples: &[u64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        let quantile = |q: f64| {
+            let pos = q * (sorted.len() - 1) as f64;
+            let (lo, hi) = (sorted[pos.floor() as usize], sorted[pos.ceil() as usize]);
+            lo + ((hi - lo) as f64 * pos.fract()).round() as u64
+        };
+        let sum: u128 = sorted.iter().map(|s| *s as u128).sum();
+
+        Some(Summary {
+            min: sorted[0],
+            q1: quantile(0.25),
+            median: quantile(0.5),
+            q3: quantile(0.75),
+            max: sorted[sorted.len() - 1],
+            avg: (sum / sorted.len() as u128) as u64,
+        })
+    }
+}
+
+// Rounds `max / 4` up to 1, 2, or 5 times a power of ten.
+fn tick_step(max: u64) -> u64 {
+    let rough = (max / 4).max(1);
+    let mut pow = 1;
+    while pow * 10 <= rough {
+        pow *= 10;
+    }
+    [1, 2, 5, 10]
+        .into_iter()
+        .map(|m| m * pow)
+        .find(|s| *s >= rough)
+        .unwrap()
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+// Long inputs are cut short in chart labels (the full text is in a tooltip).
+fn short(s: &str) -> String {
+    const MAX: usize = 12;
+    if s.chars().count() <= MAX {
+        escape(s)
+    } else {
+        escape(&format!("{}…", s.chars().take(MAX - 1).collect::<String>()))
+    }
+}
+
+/// Writes a single, self-contained HTML page (inline SVG and CSS, no scripts
+/// or external assets) with charts and tables for each top level benchmark:
+///   - a line chart of the average measurement against the input, one line
+///     per suite member
+///   - a box plot per input (min, quartiles, and max of the samples)
+///   - a summary table, including verification failures and panics
+///
+/// Everything is buffered until [`Reporter::ended`] is called. Like any other
+/// reporter, this can be fed from a device: run a
+/// [`BinaryReporter`](crate::reporters::BinaryReporter) there and hand its
+/// output to a [`BinaryDecoder`](crate::reporters::BinaryDecoder) on the host.
+pub struct HtmlReporter<'o, Out: Output + ?Sized> {
+    out: OutputAdapter<'o, Out>,
+    title: &'static str,
+    sections: Vec<Section>,
+}
+
+#[allow(clippy::needless_lifetimes)]
+impl HtmlReporter<'static, Void> {
+    pub fn new<'o, O: Output>(out: &'o mut O) -> HtmlReporter<'o, O> {
+        HtmlReporter::with_output(out)
+    }
+
+    pub fn new_with_fmt_write<'o, Fw: fmt::Write>(
+        out: &'o mut Fw,
+    ) -> HtmlReporter<'o, dyn fmt::Write + 'o> {
+        HtmlReporter::with_output(out)
+    }
+
+    pub fn new_with_io_write<'o, Iw: std::io::Write>(
+        out: &'o mut Iw,
+    ) -> HtmlReporter<'o, dyn std::io::Write + 'o> {
+        HtmlReporter::with_output(out)
+    }
+}
+
+impl<'o, O: Output + ?Sized> HtmlReporter<'o, O> {
+    fn with_output(out: &'o mut O) -> Self {
+        HtmlReporter {
+            out: OutputAdapter::new(out),
+            title: "Benchmark results",
+            sections: Vec::new(),
+        }
+    }
+
+    /// Sets the page's title (and heading); defaults to "Benchmark results".
+    pub fn set_title(mut self, title: &'static str) -> Self {
+        self.title = title;
+        self
+    }
+
+    fn current(&mut self) -> &mut Section {
+        self.sections
+            .last_mut()
+            .expect("a benchmark must be started before it is run")
+    }
+}
+
+fn fmt_raw<M: Metric>(raw: u64) -> String {
+    format!("{}", MetricFmtAdapter::<M>(&M::Unit::from_raw(raw)))
+}
+
+// Draws a y axis (with grid lines and labels) that fits values up to `max`;
+// returns the function mapping raw values to y coordinates.
+fn y_axis<M: Metric>(svg: &mut String, max: u64, x0: u64, x1: u64) -> impl Fn(u64) -> f64 {
+    let max = max.max(1);
+    let step = tick_step(max);
+    let top = (max + step - 1) / step * step;
+
+    let (_, _, mt, mb) = MARGIN;
+    let plot_height = (HEIGHT - mt - mb) as f64;
+    let y = move |v: u64| (HEIGHT - mb) as f64 - v as f64 / top as f64 * plot_height;
+
+    let mut tick = 0;
+    while tick <= top {
+        let _ = write!(
+            svg,
+            "<line class=\"grid\" x1=\"{x0}\" x2=\"{x1}\" y1=\"{y:.1}\" y2=\"{y:.1}\"/>\
+             <text x=\"{}\" y=\"{y:.1}\" text-anchor=\"end\" dominant-baseline=\"middle\">{}</text>",
+            x0 - 6,
+            escape(&fmt_raw::<M>(tick)),
+            y = y(tick),
+        );
+        tick += step;
+    }
+    let _ = write!(
+        svg,
+        "<line class=\"axis\" x1=\"{x0}\" x2=\"{x0}\" y1=\"{mt}\" y2=\"{}\"/>\
+         <line class=\"axis\" x1=\"{x0}\" x2=\"{x1}\" y1=\"{b}\" y2=\"{b}\"/>",
+        HEIGHT - mb,
+        b = HEIGHT - mb,
+    );
+
+    y
+}
+
+fn line_chart<M: Metric>(s: &Section) -> String {
+    let (ml, mr, _, mb) = MARGIN;
+    let avgs: Vec<Vec<Option<u64>>> = (0..s.members.len())
+        .map(|m| {
+            (0..s.inputs.len())
+                .map(|i| Summary::of(s.samples(m, i)).map(|s| s.avg))
+                .collect()
+        })
+        .collect();
+    let max = avgs.iter().flatten().flatten().copied().max().unwrap_or(0);
+
+    let mut svg = format!(
+        "<svg width=\"{WIDTH}\" height=\"{HEIGHT}\" viewBox=\"0 0 {WIDTH} {HEIGHT}\" role=\"img\">"
+    );
+    let y = y_axis::<M>(&mut svg, max, ml, WIDTH - mr);
+
+    let n = s.inputs.len().max(1) as f64;
+    let step = (WIDTH - ml - mr) as f64 / n;
+    let x = |i: usize| ml as f64 + step * (i as f64 + 0.5);
+    for (i, input) in s.inputs.iter().enumerate() {
+        let _ = write!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{}\" text-anchor=\"middle\"><title>{}</title>{}</text>",
+            x(i),
+            HEIGHT - mb + 16,
+            escape(input),
+            short(input),
+        );
+    }
+
+    for (m, avgs) in avgs.iter().enumerate() {
+        let color = PALETTE[m % PALETTE.len()];
+        let points: Vec<String> = avgs
+            .iter()
+            .enumerate()
+            .filter_map(|(i, a)| a.map(|a| format!("{:.1},{:.1}", x(i), y(a))))
+            .collect();
+        let _ = write!(
+            svg,
+            "<polyline fill=\"none\" stroke=\"{color}\" stroke-width=\"2\" points=\"{}\"/>",
+            points.join(" "),
+        );
+        for (i, a) in avgs.iter().enumerate() {
+            if let Some(a) = a {
+                let _ = write!(
+                    svg,
+                    "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3\" fill=\"{color}\">\
+                     <title>{}: {}</title></circle>",
+                    x(i),
+                    y(*a),
+                    escape(s.members[m]),
+                    escape(&fmt_raw::<M>(*a)),
+                );
+            }
+        }
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+fn box_plot<M: Metric>(s: &Section, input_idx: usize) -> String {
+    let (ml, mr, _, _) = MARGIN;
+    let summaries: Vec<Option<Summary>> = (0..s.members.len())
+        .map(|m| Summary::of(s.samples(m, input_idx)))
+        .collect();
+    let max = summaries.iter().flatten().map(|s| s.max).max().unwrap_or(0);
+
+    let width = ml + mr + BOX_WIDTH;
+    let mut svg = format!(
+        "<svg width=\"{width}\" height=\"{HEIGHT}\" viewBox=\"0 0 {width} {HEIGHT}\" role=\"img\">"
+    );
+    let y = y_axis::<M>(&mut svg, max, ml, width - mr);
+
+    let slot = BOX_WIDTH as f64 / s.members.len().max(1) as f64;
+    let half = (slot / 3.0).min(20.0);
+    for (m, summary) in summaries.iter().enumerate() {
+        let b = match summary {
+            Some(b) => b,
+            None => continue,
+        };
+        let color = PALETTE[m % PALETTE.len()];
+        let cx = ml as f64 + slot * (m as f64 + 0.5);
+        let _ = write!(
+            svg,
+            "<g stroke=\"{color}\"><title>{}: min {}, median {}, max {}</title>\
+             <line x1=\"{cx:.1}\" x2=\"{cx:.1}\" y1=\"{:.1}\" y2=\"{:.1}\"/>\
+             <rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{color}\" fill-opacity=\"0.3\"/>\
+             <line x1=\"{:.1}\" x2=\"{:.1}\" y1=\"{:.1}\" y2=\"{:.1}\" stroke-width=\"2\"/></g>",
+            escape(s.members[m]),
+            escape(&fmt_raw::<M>(b.min)),
+            escape(&fmt_raw::<M>(b.median)),
+            escape(&fmt_raw::<M>(b.max)),
+            y(b.min),
+            y(b.max),
+            cx - half,
+            y(b.q3),
+            2.0 * half,
+            y(b.q1) - y(b.q3),
+            cx - half,
+            cx + half,
+            y(b.median),
+            y(b.median),
+        );
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+fn table<M: Metric>(s: &Section) -> String {
+    let mut t = String::from("<table><tr><th>input</th>");
+    for m in &s.members {
+        let _ = write!(t, "<th>{}</th>", escape(m));
+    }
+    t.push_str("</tr>");
+
+    for (i, input) in s.inputs.iter().enumerate() {
+        let _ = write!(t, "<tr><td>{}</td>", escape(input));
+        for m in 0..s.members.len() {
+            let problem = s.problems.iter().any(|(pm, pi, _)| (*pm, *pi) == (m, i));
+            let class = if problem { " class=\"bad\"" } else { "" };
+            let _ = match Summary::of(s.samples(m, i)) {
+                Some(b) => write!(
+                    t,
+                    "<td{class}>{}{} <small>({} to {})</small></td>",
+                    if problem { "✗ " } else { "" },
+                    escape(&fmt_raw::<M>(b.avg)),
+                    escape(&fmt_raw::<M>(b.min)),
+                    escape(&fmt_raw::<M>(b.max)),
+                ),
+                None if problem => write!(t, "<td{class}>✗</td>"),
+                None => write!(t, "<td>-</td>"),
+            };
+        }
+        t.push_str("</tr>");
+    }
+    t.push_str("</table>");
+
+    if !s.problems.is_empty() {
+        t.push_str("<ul class=\"bad\">");
+        for (m, i, msg) in &s.problems {
+            let _ = write!(
+                t,
+                "<li><code>{}</code> on <code>{}</code>: {}</li>",
+                escape(s.members[*m]),
+                escape(&s.inputs[*i]),
+                escape(msg),
+            );
+        }
+        t.push_str("</ul>");
+    }
+
+    t
+}
+
+fn section<M: Metric>(s: &Section) -> String {
+    let mut html = format!("<section><h2>{}</h2>", escape(s.name));
+
+    if s.members.len() > 1 {
+        html.push_str("<p class=\"legend\">");
+        for (m, name) in s.members.iter().enumerate() {
+            let _ = write!(
+                html,
+                "<span style=\"color:{}\">■ {}</span>",
+                PALETTE[m % PALETTE.len()],
+                escape(name),
+            );
+        }
+        html.push_str("</p>");
+    }
+
+    html.push_str(&line_chart::<M>(s));
+    html.push_str("<div class=\"boxes\">");
+    for (i, input) in s.inputs.iter().enumerate() {
+        let _ = write!(
+            html,
+            "<figure>{}<figcaption title=\"{}\">{}</figcaption></figure>",
+            box_plot::<M>(s, i),
+            escape(input),
+            short(input),
+        );
+    }
+    html.push_str("</div>");
+    html.push_str(&table::<M>(s));
+    html.push_str("</section>");
+    html
+}
+
+impl<'o, O, M> Reporter<M> for HtmlReporter<'o, O>
+where
+    O: Output + ?Sized,
+    M: Metric,
+{
+    type Error = O::Error;
+
+    fn starting_single_benchmark(
+        &mut self,
+        name: &'static str,
+        _inputs_size_hint: (usize, Option<usize>),
+        _iterations: usize,
+    ) {
+        self.sections.push(Section::new(name, vec![name]));
+    }
+
+    fn single_benchmark_run(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        _iteration_idx: usize,
+        measurement: M::Unit,
+    ) {
+        let s = self.current();
+        s.input(input_idx, input);
+        s.samples_mut(0, input_idx).push(measurement.to_raw());
+    }
+
+    fn starting_new_benchmark_suite<I: Iterator<Item = (&'static str, usize)> + Clone>(
+        &mut self,
+        name: &'static str,
+        _inputs_size_hint: (usize, Option<usize>),
+        benchmarks: I,
+        _axes: &[Axis<'_>],
+    ) {
+        self.sections
+            .push(Section::new(name, benchmarks.map(|(n, _)| n).collect()));
+    }
+
+    fn suite_benchmark_run(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_idx: usize,
+        _benchmark_name: &'static str,
+        _iteration_idx: usize,
+        _num_iterations: usize,
+        measurement: M::Unit,
+    ) {
+        let s = self.current();
+        s.input(input_idx, input);
+        s.samples_mut(benchmark_idx, input_idx)
+            .push(measurement.to_raw());
+    }
+
+    fn verification_failed(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_name: &'static str,
+        _iteration_idx: usize,
+        message: &'static str,
+    ) {
+        let s = self.current();
+        s.input(input_idx, input);
+        let member = s.member_of(benchmark_name);
+        s.problems.push((
+            member,
+            input_idx,
+            format!("failed verification: {}", message),
+        ));
+    }
+
+    fn benchmark_panicked(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_name: &'static str,
+        message: &str,
+    ) {
+        let s = self.current();
+        s.input(input_idx, input);
+        let member = s.member_of(benchmark_name);
+        s.problems
+            .push((member, input_idx, format!("panicked: {}", message)));
+    }
+
+    fn ended(&mut self) {
+        write!(
+            self.out,
+            "<!DOCTYPE html>\n<html lang=\"en\"><head><meta charset=\"utf-8\">\
+             <title>{title}</title><style>{STYLE}</style></head><body>\
+             <h1>{title}</h1><p>Measuring <b>{}</b>.</p>\n",
+            escape(M::UNIT_NAME),
+            title = escape(self.title),
+        );
+        for s in core::mem::take(&mut self.sections) {
+            let section = section::<M>(&s);
+            writeln!(self.out, "{}", section);
+        }
+        writeln!(self.out, "</body></html>");
+        self.out.flush();
+    }
+
+    fn take_error(&mut self) -> Option<Self::Error> {
+        self.out.take_error()
+    }
+}
@@ -1,49 +1,99 @@
-use core::fmt;
+use core::{convert::Infallible, fmt};
 
 // Unifies `core::fmt::Write`, `embedded-hal::serial::Write`, `std::io::Write`.
+//
+// Reporters don't stop at the first error; they hold on to it (and skip the
+// rest of their output) so it can be returned from `BenchmarkRunner::run`.
 pub trait Output {
-    fn output_str(&mut self, s: &str);
-    fn output_char(&mut self, c: char) {
+    type Error;
+
+    fn output_str(&mut self, s: &str) -> Result<(), Self::Error>;
+    fn output_char(&mut self, c: char) -> Result<(), Self::Error> {
         self.output_str(c.encode_utf8(&mut [0; 4]))
     }
-    fn output_fmt(&mut self, args: fmt::Arguments<'_>) {
-        let mut s = OutputAdapter(self);
-        fmt::write(&mut s, args).unwrap()
+    fn output_fmt(&mut self, args: fmt::Arguments<'_>) -> Result<(), Self::Error> {
+        let mut s = OutputAdapter::new(self);
+        // Errors from the output are in `s`; errors from the `Display`/`Debug`
+        // impls being formatted aren't the output's fault:
+        let _ = fmt::write(&mut s, args);
+        s.take_error().map_or(Ok(()), Err)
+    }
+    // For binary reporters. Outputs that can only take text get each byte as
+    // the `char` with that code point (i.e. `0xFF` -> `'ÿ'`).
+    fn output_bytes(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        bytes.iter().try_for_each(|b| self.output_char(*b as char))
+    }
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
     }
-    fn flush(&mut self) {}
 }
 
 pub struct Void;
 impl Output for Void {
-    fn output_str(&mut self, _s: &str) {}
-    fn output_fmt(&mut self, _args: fmt::Arguments<'_>) {}
+    type Error = Infallible;
+
+    fn output_str(&mut self, _s: &str) -> Result<(), Infallible> {
+        Ok(())
+    }
+    fn output_fmt(&mut self, _args: fmt::Arguments<'_>) -> Result<(), Infallible> {
+        Ok(())
+    }
+    fn output_bytes(&mut self, _bytes: &[u8]) -> Result<(), Infallible> {
+        Ok(())
+    }
 }
 
 impl Output for dyn fmt::Write + '_ {
+    type Error = fmt::Error;
+
     #[inline(always)]
-    fn output_str(&mut self, s: &str) {
-        self.write_str(s).unwrap();
+    fn output_str(&mut self, s: &str) -> fmt::Result {
+        self.write_str(s)
     }
 
     #[inline(always)]
-    fn output_char(&mut self, c: char) {
-        self.write_char(c).unwrap()
+    fn output_char(&mut self, c: char) -> fmt::Result {
+        self.write_char(c)
     }
 
-    fn output_fmt(&mut self, args: fmt::Arguments<'_>) {
-        self.write_fmt(args).unwrap()
+    fn output_fmt(&mut self, args: fmt::Arguments<'_>) -> fmt::Result {
+        self.write_fmt(args)
     }
 }
 
 #[cfg(feature = "std")]
 #[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "std")))]
 impl Output for dyn std::io::Write + '_ {
-    fn output_str(&mut self, s: &str) {
-        self.write_all(s.as_bytes()).unwrap();
+    type Error = std::io::Error;
+
+    fn output_str(&mut self, s: &str) -> std::io::Result<()> {
+        self.write_all(s.as_bytes())
+    }
+
+    fn output_bytes(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        self.write_all(bytes)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        std::io::Write::flush(self)
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "embedded-io")))]
+impl<E: embedded_io::Error> Output for dyn embedded_io::Write<Error = E> + '_ {
+    type Error = E;
+
+    fn output_str(&mut self, s: &str) -> Result<(), E> {
+        self.write_all(s.as_bytes())
+    }
+
+    fn output_bytes(&mut self, bytes: &[u8]) -> Result<(), E> {
+        self.write_all(bytes)
     }
 
-    fn flush(&mut self) {
-        std::io::Write::flush(self).unwrap()
+    fn flush(&mut self) -> Result<(), E> {
+        embedded_io::Write::flush(self)
     }
 }
 
@@ -51,35 +101,85 @@ impl Output for dyn std::io::Write + '_ {
 #[cfg(feature = "embedded-hal")]
 #[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "embedded-hal")))]
 impl<W: embedded_hal::serial::Write<u8>> Output for W {
-    fn output_str(&mut self, s: &str) {
-        s.as_bytes()
-            .iter()
-            .for_each(|c| nb::block!(self.write(*c)).map_err(|_| ()).unwrap());
+    type Error = W::Error;
+
+    fn output_str(&mut self, s: &str) -> Result<(), W::Error> {
+        self.output_bytes(s.as_bytes())
+    }
+
+    fn output_bytes(&mut self, bytes: &[u8]) -> Result<(), W::Error> {
+        bytes.iter().try_for_each(|c| nb::block!(self.write(*c)))
     }
 
-    fn flush(&mut self) {
-        W::flush(self).map_err(|_| ()).unwrap();
+    fn flush(&mut self) -> Result<(), W::Error> {
+        nb::block!(W::flush(self))
     }
 }
 
-pub(super) struct OutputAdapter<'o, O: ?Sized + Output = dyn Output>(pub(super) &'o mut O);
+// Holds on to the first error `O` runs into; nothing else is written until
+// it's taken.
+pub(super) struct OutputAdapter<'o, O: ?Sized + Output> {
+    out: &'o mut O,
+    error: Option<O::Error>,
+}
+
+impl<'o, O: ?Sized + Output> OutputAdapter<'o, O> {
+    pub(super) fn new(out: &'o mut O) -> Self {
+        OutputAdapter { out, error: None }
+    }
+
+    fn check(&mut self, f: impl FnOnce(&mut O) -> Result<(), O::Error>) {
+        if self.error.is_none() {
+            self.error = f(self.out).err();
+        }
+    }
+
+    pub(super) fn bytes(&mut self, bytes: &[u8]) {
+        self.check(|o| o.output_bytes(bytes))
+    }
+
+    pub(super) fn flush(&mut self) {
+        self.check(|o| o.flush())
+    }
+
+    pub(super) fn take_error(&mut self) -> Option<O::Error> {
+        self.error.take()
+    }
+
+    // These take the place of the `fmt::Write` methods (for `write!` too) so
+    // callers don't have to deal with errors that can't happen.
+
+    pub(super) fn write_str(&mut self, s: &str) {
+        let _ = fmt::Write::write_str(self, s);
+    }
+
+    #[cfg_attr(not(feature = "std"), allow(dead_code))] // (only `tables` uses it)
+    pub(super) fn write_char(&mut self, c: char) {
+        let _ = fmt::Write::write_char(self, c);
+    }
+
+    pub(super) fn write_fmt(&mut self, args: fmt::Arguments<'_>) {
+        let _ = fmt::Write::write_fmt(self, args);
+    }
+}
 
+// Never fails; see `take_error`.
 impl<'o, O: ?Sized + Output> fmt::Write for OutputAdapter<'o, O> {
     #[inline(always)]
     fn write_str(&mut self, s: &str) -> fmt::Result {
-        self.0.output_str(s);
+        self.check(|o| o.output_str(s));
         Ok(())
     }
 
     #[inline(always)]
     fn write_char(&mut self, c: char) -> fmt::Result {
-        self.0.output_char(c);
+        self.check(|o| o.output_char(c));
         Ok(())
     }
 
     #[inline(always)]
     fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> fmt::Result {
-        self.0.output_fmt(args);
+        self.check(|o| o.output_fmt(args));
         Ok(())
     }
 }
@@ -1,11 +1,18 @@
-use core::fmt::Debug;
+use core::{convert::Infallible, fmt::Debug};
 
-use crate::Metric;
+use crate::{runner::Axis, Metric};
 
 #[allow(unused_variables)]
 pub trait Reporter<M: Metric> {
+    // What can go wrong while writing out the report (i.e.
+    // `std::io::Error`); reporters that can't fail use `Infallible`.
+    //
+    // There's no default (associated type defaults aren't stable yet) so
+    // reporters written before this was added need a
+    // `type Error = core::convert::Infallible;` line.
+    type Error;
+
     fn top_level_benchmarks<I: Iterator<Item = &'static str> + Clone>(&mut self, names: I) {}
-    fn num_iterations(&mut self, iterations: usize) {}
 
     // single benchmarks go in this order:
     // input 1:
@@ -17,12 +24,19 @@ pub trait Reporter<M: Metric> {
     //   + iteration 2
     //     ...
     //
+    // `iterations` is the number of iterations each input is run for (at
+    // most, for benchmarks with a budget).
     fn starting_single_benchmark(
         &mut self,
         name: &'static str,
         inputs_size_hint: (usize, Option<usize>),
+        iterations: usize,
     ) {
     }
+    // Called before the first run of each input when the benchmark has a
+    // budget (see `Config::budget`); `iterations` takes the place of the count
+    // given to `starting_single_benchmark` for this input.
+    fn single_benchmark_iterations(&mut self, input_idx: usize, iterations: usize) {}
     fn single_benchmark_run(
         &mut self,
         input_idx: usize,
@@ -46,14 +60,23 @@ pub trait Reporter<M: Metric> {
     // input 2:
     //  ...
     //
-    //
-    fn starting_new_benchmark_suite<I: Iterator<Item = &'static str> + Clone>(
+    // `benchmarks` yields the name and the number of iterations of each
+    // benchmark in the suite; iteration counts can differ between benchmarks.
+    // Benchmarks with a budget (see `Config::budget`) may run fewer; each run
+    // carries the count for its (benchmark, input) pair.
+    fn starting_new_benchmark_suite<I: Iterator<Item = (&'static str, usize)> + Clone>(
         &mut self,
         name: &'static str,
         inputs_size_hint: (usize, Option<usize>),
-        benchmark_names: I,
+        benchmarks: I,
+        axes: &[Axis<'_>],
     ) {
     }
+    // `axes` is empty unless the suite was made with `matrix`; for those,
+    // this is also called before the first run of each input. Use
+    // `Axis::coordinate` to find where `input_idx` falls along each axis.
+    fn suite_input_axes(&mut self, input_idx: usize, axes: &[Axis<'_>]) {}
+    #[allow(clippy::too_many_arguments)]
     fn suite_benchmark_run(
         &mut self,
         input_idx: usize,
@@ -61,18 +84,59 @@ pub trait Reporter<M: Metric> {
         benchmark_idx: usize,
         benchmark_name: &'static str,
         iteration_idx: usize,
+        num_iterations: usize,
         measurement: M::Unit,
     ) {
     }
     fn ending_benchmark_suite(&mut self, name: &'static str) {}
 
+    // Called when the result of a run fails `Benchmark::verify`, right before
+    // the `single_benchmark_run`/`suite_benchmark_run` call for that
+    // iteration. This is called at most once per (benchmark, input) pair.
+    fn verification_failed(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_name: &'static str,
+        iteration_idx: usize,
+        message: &'static str,
+    ) {
+    }
+
+    // Called when a benchmark panics while running on an input (only when
+    // panics are being caught; see `Config::catch_panics`). This takes the
+    // place of the rest of the `single_benchmark_run`/`suite_benchmark_run`
+    // calls for that (benchmark, input) pair; some iterations may have
+    // already been reported.
+    fn benchmark_panicked(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_name: &'static str,
+        message: &str,
+    ) {
+    }
+
     fn ended(&mut self) {}
+
+    // The first error the reporter ran into, if any; reporters skip the rest
+    // of their output after an error instead of panicking.
+    // `BenchmarkRunner::run` returns this once the run has ended.
+    fn take_error(&mut self) -> Option<Self::Error> {
+        None
+    }
 }
 
 /// A placeholder reporter that does nothing.
 pub struct NoOpReporter;
 
-impl<M: Metric> Reporter<M> for NoOpReporter {}
+impl<M: Metric> Reporter<M> for NoOpReporter {
+    type Error = Infallible;
+}
+
+// `(A, B)` reporters (to drive several reporters at once) and `&mut R`.
+mod tee;
+pub use tee::EitherError;
 
 macro_rules! feature_gated {
     ($mod_name:ident gated with: $($cfg_expr:tt)*) => {
@@ -91,8 +155,34 @@ macro_rules! feature_gated {
 
 mod io;
 
+mod buffered;
+pub use buffered::*;
+feature_gated![blocking gated on "embedded-io-async"];
+
 mod basic;
 pub use basic::*;
+
+pub mod binary;
+pub use binary::BinaryReporter;
+#[cfg(feature = "std")]
+#[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "std")))]
+pub use binary::{BinaryDecoder, DecodeError};
+
+mod frame_lines;
+pub use frame_lines::*;
+
+feature_gated![defmt_output gated on "defmt"];
+feature_gated![semihosting gated on "semihosting"];
+feature_gated![rtt gated on "rtt"];
+
+mod aggregating;
+pub use aggregating::*;
+
+mod progress;
+pub use progress::*;
+
+feature_gated![tables gated on "std"];
+feature_gated![html gated on "std"];
 // feature_gated![basic gated with: any(feature = "embedded-hal", feature = "std")];
 
 // host side only, has:
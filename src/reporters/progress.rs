@@ -0,0 +1,487 @@
+use core::fmt::{self, Debug};
+
+use super::io::{Output, OutputAdapter, Void};
+use super::{EitherError, Reporter};
+use crate::metrics::{Metric, RawUnit};
+use crate::runner::Axis;
+
+/// How a [`ProgressReporter`] shows progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressStyle {
+    /// A single line that is redrawn in place (every 100ms with `std`, every
+    /// 100 iterations otherwise); meant for terminals (it uses `\r` and the
+    /// ANSI "erase line" sequence), see [`ProgressStyle::detect`].
+    ///
+    /// The line is erased before calls into the wrapped reporter that usually
+    /// print something (everything but runs, except for the last run of each
+    /// benchmark and input) so reporters writing to the same terminal don't
+    /// end up interleaved with it.
+    StatusLine,
+    /// A compact line every `every` iterations (and at the start of each top
+    /// level benchmark); meant for logs and serial ports.
+    Heartbeat { every: usize },
+}
+
+impl ProgressStyle {
+    /// [`StatusLine`](ProgressStyle::StatusLine) if `stream` is a terminal
+    /// that ANSI escapes can be used with (`TERM` isn't `dumb`; whether
+    /// colors are wanted doesn't matter), a
+    /// [`Heartbeat`](ProgressStyle::Heartbeat) every `every` iterations
+    /// otherwise.
+    #[cfg(feature = "std")]
+    #[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "std")))]
+    pub fn detect(stream: super::Stream, every: usize) -> Self {
+        if super::basic::terminal(stream).ansi {
+            ProgressStyle::StatusLine
+        } else {
+            ProgressStyle::Heartbeat { every }
+        }
+    }
+}
+
+/// Wraps a [`Reporter`], showing what's running and an estimate of the time
+/// left while a long run is going.
+///
+/// Progress is tracked per top level benchmark using the number of top
+/// level benchmarks, the inputs' size hints, and the iteration counts the
+/// runner passes along. The estimate of what's left is for the current top
+/// level benchmark only (the `[2/5]` shows how many are still to come):
+/// - with `std`, it's extrapolated from the (wall clock) time spent on the
+///   top level benchmark so far and shown once that's at least a second
+/// - otherwise, it's extrapolated from the measurements so far and shown in
+///   the [`Metric`]'s unit (i.e. `~52000 cycles left`); this leaves out
+///   everything that isn't measured (setup, verification, reporting, etc.)
+///
+/// Wrap [`NoOpReporter`](super::NoOpReporter) to only show progress.
+pub struct ProgressReporter<'o, Out: Output + ?Sized, R> {
+    inner: R,
+    out: OutputAdapter<'o, Out>,
+    style: ProgressStyle,
+    num_top_level: usize,
+    top_level_idx: usize,
+    name: &'static str,
+    member: Option<&'static str>,
+    // Best guess at the number of inputs; 0 if unknown.
+    num_inputs: usize,
+    iterations_per_input: usize,
+    input_idx: usize,
+    // Iterations run for the inputs before the current one.
+    done_before_input: usize,
+    // Iterations the current input gets (less than `iterations_per_input`
+    // for single benchmarks with a budget).
+    input_iterations: usize,
+    // Iterations run for the current input (across all suite members).
+    done_for_input: usize,
+    // When the current top level benchmark started.
+    #[cfg(feature = "std")]
+    started: std::time::Instant,
+    // The sum of the current top level benchmark's measurements.
+    #[cfg(not(feature = "std"))]
+    spent: u64,
+    #[cfg(feature = "std")]
+    last_drawn: std::time::Instant,
+    since_drawn: usize,
+    drawn: bool,
+}
+
+// How often `StatusLine`s are redrawn.
+#[cfg(feature = "std")]
+const REDRAW_EVERY: core::time::Duration = core::time::Duration::from_millis(100);
+#[cfg(not(feature = "std"))]
+const REDRAW_EVERY: usize = 100;
+
+#[allow(clippy::needless_lifetimes)]
+impl ProgressReporter<'static, Void, ()> {
+    pub fn new<'o, R, O: Output>(
+        inner: R,
+        out: &'o mut O,
+        style: ProgressStyle,
+    ) -> ProgressReporter<'o, O, R> {
+        ProgressReporter::with_output(inner, out, style)
+    }
+
+    #[cfg(feature = "embedded-hal")]
+    #[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "embedded-hal")))]
+    pub fn new_with_serial<'o, R, S>(
+        inner: R,
+        out: &'o mut S,
+        style: ProgressStyle,
+    ) -> ProgressReporter<'o, S, R>
+    where
+        S: embedded_hal::serial::Write<u8>,
+    {
+        ProgressReporter::with_output(inner, out, style)
+    }
+
+    pub fn new_with_fmt_write<'o, R, Fw: fmt::Write>(
+        inner: R,
+        out: &'o mut Fw,
+        style: ProgressStyle,
+    ) -> ProgressReporter<'o, dyn fmt::Write + 'o, R> {
+        ProgressReporter::with_output(inner, out, style)
+    }
+
+    #[cfg(feature = "embedded-io")]
+    #[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "embedded-io")))]
+    pub fn new_with_embedded_io<'o, R, Ew: embedded_io::Write>(
+        inner: R,
+        out: &'o mut Ew,
+        style: ProgressStyle,
+    ) -> ProgressReporter<'o, dyn embedded_io::Write<Error = Ew::Error> + 'o, R> {
+        ProgressReporter::with_output(inner, out, style)
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "std")))]
+    pub fn new_with_io_write<'o, R, Iw: std::io::Write>(
+        inner: R,
+        out: &'o mut Iw,
+        style: ProgressStyle,
+    ) -> ProgressReporter<'o, dyn std::io::Write + 'o, R> {
+        ProgressReporter::with_output(inner, out, style)
+    }
+}
+
+impl<'o, O: Output + ?Sized, R> ProgressReporter<'o, O, R> {
+    fn with_output(inner: R, out: &'o mut O, style: ProgressStyle) -> Self {
+        if let ProgressStyle::Heartbeat { every } = style {
+            assert!(every > 0, "heartbeats must be at least 1 iteration apart");
+        }
+
+        ProgressReporter {
+            inner,
+            out: OutputAdapter::new(out),
+            style,
+            num_top_level: 0,
+            top_level_idx: 0,
+            name: "",
+            member: None,
+            num_inputs: 0,
+            iterations_per_input: 0,
+            input_idx: 0,
+            done_before_input: 0,
+            input_iterations: 0,
+            done_for_input: 0,
+            #[cfg(feature = "std")]
+            started: std::time::Instant::now(),
+            #[cfg(not(feature = "std"))]
+            spent: 0,
+            #[cfg(feature = "std")]
+            last_drawn: std::time::Instant::now(),
+            since_drawn: 0,
+            drawn: false,
+        }
+    }
+
+    /// Gives back the wrapped reporter.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn start_top_level(
+        &mut self,
+        name: &'static str,
+        inputs_size_hint: (usize, Option<usize>),
+        iterations_per_input: usize,
+    ) {
+        self.top_level_idx += 1;
+        self.name = name;
+        self.member = None;
+        self.num_inputs = inputs_size_hint.1.unwrap_or(inputs_size_hint.0);
+        self.iterations_per_input = iterations_per_input;
+        self.input_idx = 0;
+        self.done_before_input = 0;
+        self.input_iterations = iterations_per_input;
+        self.done_for_input = 0;
+        #[cfg(feature = "std")]
+        {
+            self.started = std::time::Instant::now();
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            self.spent = 0;
+        }
+        // So there's a heartbeat as each top level benchmark starts:
+        self.since_drawn = usize::MAX;
+    }
+
+    fn start_input(&mut self, input_idx: usize) {
+        if input_idx != self.input_idx {
+            self.input_idx = input_idx;
+            self.done_before_input += self.done_for_input;
+            self.input_iterations = self.iterations_per_input;
+            self.done_for_input = 0;
+        }
+    }
+
+    fn record(&mut self, input_idx: usize, measurement: u64) {
+        self.start_input(input_idx);
+        self.done_for_input += 1;
+        self.since_drawn = self.since_drawn.saturating_add(1);
+
+        #[cfg(not(feature = "std"))]
+        {
+            self.spent = self.spent.saturating_add(measurement);
+        }
+        let _ = measurement;
+    }
+
+    // Erases the status line (if it's up) before the wrapped reporter gets to
+    // write anything.
+    fn clear(&mut self) {
+        if self.drawn {
+            self.out.write_str("\r\x1b[2K");
+            self.out.flush();
+            self.drawn = false;
+        }
+    }
+
+    // `(done, total)` iterations for the current top level benchmark; `total`
+    // is 0 if the number of inputs isn't known.
+    fn iterations(&self) -> (usize, usize) {
+        let done = self.done_before_input + self.done_for_input;
+        if self.num_inputs == 0 {
+            return (done, 0);
+        }
+
+        // Size hints can be off; don't go past 100%.
+        let inputs_left = self.num_inputs.saturating_sub(self.input_idx + 1);
+        let total = self.done_before_input
+            + self.input_iterations.max(self.done_for_input)
+            + inputs_left * self.iterations_per_input;
+        (done, total)
+    }
+
+    // `[2/5] name › member: input 3/10`
+    fn what(&mut self) {
+        if self.num_top_level != 0 {
+            write!(self.out, "[{}/{}] ", self.top_level_idx, self.num_top_level);
+        }
+        self.out.write_str(self.name);
+        if let Some(member) = self.member {
+            write!(self.out, " › {}", member);
+        }
+        write!(self.out, ": input {}", self.input_idx + 1);
+        if self.num_inputs != 0 {
+            write!(self.out, "/{}", self.num_inputs.max(self.input_idx + 1));
+        }
+    }
+
+    // `unit` is the `Metric`'s unit name.
+    fn eta(&mut self, unit: &'static str) {
+        let (done, total) = self.iterations();
+        if total == 0 || done == 0 {
+            return;
+        }
+        write!(self.out, ", {}%", done * 100 / total);
+
+        #[cfg(not(feature = "std"))]
+        if self.spent != 0 && total != done {
+            let left = self.spent as u128 * (total - done) as u128 / done as u128;
+            write!(self.out, ", ~{} {} left", left, unit);
+        }
+        let _ = unit;
+
+        #[cfg(feature = "std")]
+        {
+            let spent = self.started.elapsed();
+            if spent.as_secs() >= 1 {
+                let left = spent.as_secs_f64() * (total - done) as f64 / done as f64;
+                let left = left.round() as u64;
+                match (left / 3600, left / 60 % 60, left % 60) {
+                    (0, 0, s) => write!(self.out, ", ~{}s left", s),
+                    (0, m, s) => write!(self.out, ", ~{}m {}s left", m, s),
+                    (h, m, _) => write!(self.out, ", ~{}h {}m left", h, m),
+                }
+            }
+        }
+    }
+
+    fn draw(&mut self, unit: &'static str) {
+        match self.style {
+            ProgressStyle::StatusLine => {
+                #[cfg(feature = "std")]
+                let due = self.last_drawn.elapsed() >= REDRAW_EVERY;
+                #[cfg(not(feature = "std"))]
+                let due = self.since_drawn >= REDRAW_EVERY;
+                if self.drawn && !due {
+                    return;
+                }
+
+                self.clear();
+                self.since_drawn = 0;
+                #[cfg(feature = "std")]
+                {
+                    self.last_drawn = std::time::Instant::now();
+                }
+                self.what();
+                self.eta(unit);
+                self.out.flush();
+                self.drawn = true;
+            }
+            ProgressStyle::Heartbeat { every } => {
+                if self.since_drawn < every {
+                    return;
+                }
+                self.since_drawn = 0;
+                self.out.write_str("~ ");
+                self.what();
+                self.eta(unit);
+                self.out.write_str("\r\n");
+                self.out.flush();
+            }
+        }
+    }
+}
+
+impl<'o, O, R, M> Reporter<M> for ProgressReporter<'o, O, R>
+where
+    O: Output + ?Sized,
+    R: Reporter<M>,
+    M: Metric,
+{
+    type Error = EitherError<R::Error, O::Error>;
+
+    fn top_level_benchmarks<I: Iterator<Item = &'static str> + Clone>(&mut self, names: I) {
+        self.num_top_level = names.clone().count();
+        self.top_level_idx = 0;
+        self.inner.top_level_benchmarks(names);
+    }
+
+    fn starting_single_benchmark(
+        &mut self,
+        name: &'static str,
+        inputs_size_hint: (usize, Option<usize>),
+        iterations: usize,
+    ) {
+        self.clear();
+        self.inner
+            .starting_single_benchmark(name, inputs_size_hint, iterations);
+
+        self.start_top_level(name, inputs_size_hint, iterations);
+        self.draw(M::UNIT_NAME);
+    }
+
+    fn single_benchmark_iterations(&mut self, input_idx: usize, iterations: usize) {
+        self.inner
+            .single_benchmark_iterations(input_idx, iterations);
+
+        self.start_input(input_idx);
+        self.input_iterations = iterations;
+    }
+
+    fn single_benchmark_run(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        iteration_idx: usize,
+        measurement: M::Unit,
+    ) {
+        // (Reporters print once an input's last run is in.)
+        self.start_input(input_idx);
+        if iteration_idx + 1 >= self.input_iterations {
+            self.clear();
+        }
+        self.inner
+            .single_benchmark_run(input_idx, input, iteration_idx, measurement);
+
+        self.record(input_idx, measurement.to_raw());
+        self.draw(M::UNIT_NAME);
+    }
+
+    fn ending_single_benchmark(&mut self, name: &'static str) {
+        self.clear();
+        self.inner.ending_single_benchmark(name);
+    }
+
+    fn starting_new_benchmark_suite<I: Iterator<Item = (&'static str, usize)> + Clone>(
+        &mut self,
+        name: &'static str,
+        inputs_size_hint: (usize, Option<usize>),
+        benchmarks: I,
+        axes: &[Axis<'_>],
+    ) {
+        self.clear();
+        let per_input = benchmarks.clone().map(|(_, it)| it).sum();
+        self.inner
+            .starting_new_benchmark_suite(name, inputs_size_hint, benchmarks, axes);
+
+        self.start_top_level(name, inputs_size_hint, per_input);
+        self.draw(M::UNIT_NAME);
+    }
+
+    fn suite_input_axes(&mut self, input_idx: usize, axes: &[Axis<'_>]) {
+        self.clear();
+        self.inner.suite_input_axes(input_idx, axes);
+    }
+
+    fn suite_benchmark_run(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_idx: usize,
+        benchmark_name: &'static str,
+        iteration_idx: usize,
+        num_iterations: usize,
+        measurement: M::Unit,
+    ) {
+        if iteration_idx + 1 >= num_iterations {
+            self.clear();
+        }
+        self.inner.suite_benchmark_run(
+            input_idx,
+            input,
+            benchmark_idx,
+            benchmark_name,
+            iteration_idx,
+            num_iterations,
+            measurement,
+        );
+
+        self.member = Some(benchmark_name);
+        self.record(input_idx, measurement.to_raw());
+        self.draw(M::UNIT_NAME);
+    }
+
+    fn ending_benchmark_suite(&mut self, name: &'static str) {
+        self.clear();
+        self.inner.ending_benchmark_suite(name);
+    }
+
+    fn verification_failed(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_name: &'static str,
+        iteration_idx: usize,
+        message: &'static str,
+    ) {
+        self.clear();
+        self.inner
+            .verification_failed(input_idx, input, benchmark_name, iteration_idx, message);
+    }
+
+    fn benchmark_panicked(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_name: &'static str,
+        message: &str,
+    ) {
+        self.clear();
+        self.inner
+            .benchmark_panicked(input_idx, input, benchmark_name, message);
+    }
+
+    fn ended(&mut self) {
+        self.clear();
+        self.inner.ended();
+    }
+
+    fn take_error(&mut self) -> Option<Self::Error> {
+        let (inner, out) = (self.inner.take_error(), self.out.take_error());
+        inner
+            .map(EitherError::First)
+            .or_else(|| out.map(EitherError::Second))
+    }
+}
@@ -0,0 +1,152 @@
+use core::cell::UnsafeCell;
+use core::convert::Infallible;
+use core::ptr;
+use core::sync::atomic::{fence, AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+use super::io::Output;
+
+/// An [`Output`] that writes to a SEGGER RTT up-channel, for boards without a
+/// spare UART; `probe-rs`, OpenOCD, and J-Link's tools can all show what's
+/// written.
+///
+/// This sets up its own RTT control block (the `_SEGGER_RTT` symbol) with a
+/// single up-channel so it can't be used alongside another RTT
+/// implementation (i.e. `rtt-target` or `defmt-rtt`; use a `DefmtReporter`
+/// with the latter).
+///
+/// Writes wait for the debugger to make room when the channel's buffer
+/// ([`RttOutput::BUFFER_SIZE`] bytes) is full, so nothing is lost but output
+/// stalls without a debugger attached.
+pub struct RttOutput {
+    _private: (),
+}
+
+// The layouts the debugger expects; see `SEGGER_RTT.h`. `usize` is `unsigned`
+// on the 32-bit targets RTT is used on.
+#[repr(C)]
+pub(crate) struct ControlBlock {
+    id: UnsafeCell<[u8; 16]>,
+    max_up: AtomicUsize,
+    max_down: AtomicUsize,
+    pub(crate) up: Channel,
+}
+
+#[repr(C)]
+pub(crate) struct Channel {
+    name: AtomicPtr<u8>,
+    pub(crate) buf: AtomicPtr<u8>,
+    pub(crate) size: AtomicUsize,
+    // Written by us:
+    pub(crate) write: AtomicUsize,
+    // Written by the debugger:
+    pub(crate) read: AtomicUsize,
+    flags: AtomicUsize,
+}
+
+// Safety: `id` is only written once, by whoever gets `TAKEN`.
+unsafe impl Sync for ControlBlock {}
+
+struct Buffer(UnsafeCell<[u8; RttOutput::BUFFER_SIZE]>);
+// Safety: only the `RttOutput` (of which there's one) writes to this.
+unsafe impl Sync for Buffer {}
+
+#[no_mangle]
+pub(crate) static _SEGGER_RTT: ControlBlock = ControlBlock {
+    id: UnsafeCell::new([0; 16]),
+    max_up: AtomicUsize::new(1),
+    max_down: AtomicUsize::new(0),
+    up: Channel {
+        name: AtomicPtr::new(ptr::null_mut()),
+        buf: AtomicPtr::new(ptr::null_mut()),
+        size: AtomicUsize::new(0),
+        write: AtomicUsize::new(0),
+        read: AtomicUsize::new(0),
+        flags: AtomicUsize::new(0),
+    },
+};
+static BUFFER: Buffer = Buffer(UnsafeCell::new([0; RttOutput::BUFFER_SIZE]));
+static TAKEN: AtomicBool = AtomicBool::new(false);
+
+impl RttOutput {
+    pub const BUFFER_SIZE: usize = 1024;
+
+    /// Sets up the control block the first time it's called; `None` after
+    /// that.
+    ///
+    /// Checking for and marking the first call isn't atomic (`swap` is
+    /// missing on some targets, i.e. `thumbv6m`), so don't call this from
+    /// more than one context (i.e. from `main` and an interrupt handler)
+    /// without a critical section (i.e. `cortex_m::interrupt::free`) around
+    /// it; otherwise both calls can get an `RttOutput`.
+    pub fn take() -> Option<Self> {
+        // Not `swap`; see above.
+        if TAKEN.load(Ordering::Acquire) {
+            return None;
+        }
+        TAKEN.store(true, Ordering::Release);
+
+        let up = &_SEGGER_RTT.up;
+        up.name.store(b"Terminal\0".as_ptr() as *mut u8, Ordering::Relaxed);
+        up.buf.store(BUFFER.0.get().cast(), Ordering::Relaxed);
+        up.size.store(Self::BUFFER_SIZE, Ordering::Relaxed);
+        up.write.store(0, Ordering::Relaxed);
+        up.read.store(0, Ordering::Relaxed);
+        // "Block if FIFO full"; informational, for the debugger.
+        up.flags.store(2, Ordering::Relaxed);
+
+        // The debugger looks for the id so write it last (and backwards, so
+        // it's not there until all of it is):
+        fence(Ordering::SeqCst);
+        let id = b"SEGGER RTT";
+        for (i, b) in id.iter().enumerate().rev() {
+            // Safety: see `impl Sync for ControlBlock`.
+            unsafe { ptr::write_volatile(_SEGGER_RTT.id.get().cast::<u8>().add(i), *b) }
+        }
+
+        Some(RttOutput { _private: () })
+    }
+}
+
+impl Output for RttOutput {
+    type Error = Infallible;
+
+    fn output_str(&mut self, s: &str) -> Result<(), Infallible> {
+        self.output_bytes(s.as_bytes())
+    }
+
+    fn output_bytes(&mut self, mut bytes: &[u8]) -> Result<(), Infallible> {
+        let up = &_SEGGER_RTT.up;
+        let size = Self::BUFFER_SIZE;
+
+        while !bytes.is_empty() {
+            let write = up.write.load(Ordering::Relaxed);
+            let read = up.read.load(Ordering::Acquire);
+
+            // One byte is always left empty so a full buffer doesn't look
+            // empty; this is the room up to the end of the buffer:
+            let room = if read > write {
+                read - write - 1
+            } else if read == 0 {
+                size - write - 1
+            } else {
+                size - write
+            };
+            if room == 0 {
+                // Wait for the debugger to read some.
+                continue;
+            }
+
+            let n = room.min(bytes.len());
+            // Safety: `write + n <= size` and the debugger only reads the part
+            // of the buffer that's in between `read` and `write`.
+            unsafe {
+                let dst = BUFFER.0.get().cast::<u8>().add(write);
+                ptr::copy_nonoverlapping(bytes.as_ptr(), dst, n);
+            }
+            up.write.store((write + n) % size, Ordering::Release);
+            bytes = &bytes[n..];
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use core::fmt::{self, Display};
+
+use super::io::Output;
+
+/// An [`Output`] that writes to the debugger's console over ARM semihosting
+/// (like `cortex-m-semihosting`'s `hstdout`), for boards without a spare
+/// UART.
+///
+/// Every write stops the core until the debugger (or QEMU, with
+/// `-semihosting`) has handled it, so this is slow; it's fine between
+/// measurements but the reporter's output takes a while to come out.
+///
+/// Without a debugger attached, the semihosting call is a breakpoint with
+/// nothing to handle it and the core faults.
+pub struct SemihostingOutput {
+    handle: u32,
+}
+
+/// A semihosting call failed: the debugger couldn't open its console or
+/// didn't take everything that was written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SemihostingError;
+
+impl Display for SemihostingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("semihosting write failed")
+    }
+}
+
+// See "Semihosting for AArch32 and AArch64" from ARM.
+const SYS_OPEN: u32 = 0x01;
+const SYS_WRITE: u32 = 0x05;
+// `fopen` modes; the special file `:tt` is stdout when opened with "w" and
+// stderr when opened with "a".
+const MODE_W: u32 = 4;
+const MODE_A: u32 = 8;
+
+fn syscall(op: u32, args: &[u32]) -> u32 {
+    // Safety: `args` is what `op` expects and outlives the call.
+    unsafe { cortex_m::asm::semihosting_syscall(op, args.as_ptr() as usize as u32) }
+}
+
+impl SemihostingOutput {
+    /// The debugger's stdout.
+    pub fn stdout() -> Result<Self, SemihostingError> {
+        Self::open(MODE_W)
+    }
+
+    /// The debugger's stderr.
+    pub fn stderr() -> Result<Self, SemihostingError> {
+        Self::open(MODE_A)
+    }
+
+    fn open(mode: u32) -> Result<Self, SemihostingError> {
+        let name = b":tt\0";
+        let args = [name.as_ptr() as usize as u32, mode, name.len() as u32 - 1];
+        match syscall(SYS_OPEN, &args) {
+            u32::MAX => Err(SemihostingError),
+            handle => Ok(SemihostingOutput { handle }),
+        }
+    }
+}
+
+impl Output for SemihostingOutput {
+    type Error = SemihostingError;
+
+    fn output_str(&mut self, s: &str) -> Result<(), SemihostingError> {
+        self.output_bytes(s.as_bytes())
+    }
+
+    fn output_bytes(&mut self, bytes: &[u8]) -> Result<(), SemihostingError> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+
+        let args = [
+            self.handle,
+            bytes.as_ptr() as usize as u32,
+            bytes.len() as u32,
+        ];
+        // Returns the number of bytes that _weren't_ written.
+        match syscall(SYS_WRITE, &args) {
+            0 => Ok(()),
+            _ => Err(SemihostingError),
+        }
+    }
+}
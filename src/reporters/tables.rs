@@ -0,0 +1,635 @@
+//! Reporters that produce results meant to be pasted elsewhere: CSV for
+//! spreadsheets and GitHub-flavored Markdown tables for PR descriptions.
+
+use core::fmt::{self, Debug, Display, Write as _};
+use std::{format, string::String, vec::Vec};
+
+use super::io::{Output, OutputAdapter, Void};
+use super::Reporter;
+use crate::metrics::{Metric, MetricFmtAdapter};
+use crate::runner::Axis;
+
+// Running min/max/sum of a (benchmark, input) pair's measurements.
+#[derive(Clone, Copy)]
+struct Stats<U> {
+    count: usize,
+    min: U,
+    max: U,
+    sum: U,
+}
+
+impl<U: Copy + Ord + core::ops::Add<Output = U>> Stats<U> {
+    fn new(measurement: U) -> Self {
+        Stats {
+            count: 1,
+            min: measurement,
+            max: measurement,
+            sum: measurement,
+        }
+    }
+
+    fn add(&mut self, measurement: U) {
+        self.count += 1;
+        self.min = self.min.min(measurement);
+        self.max = self.max.max(measurement);
+        self.sum = self.sum + measurement;
+    }
+
+    fn avg<M: Metric<Unit = U>>(&self) -> U
+    where
+        U: core::ops::Div<M::Divisor, Output = U>,
+    {
+        let count: M::Divisor = self.count.try_into().map_err(|_| ()).unwrap();
+        self.sum / count
+    }
+}
+
+// Unlike `format!`, an error from the `Debug` impl only cuts the text short.
+fn to_string(v: &dyn Debug) -> String {
+    let mut s = String::new();
+    let _ = write!(s, "{:?}", v);
+    s
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// What each row of a [`CsvReporter`]'s output is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvRows {
+    /// One row per iteration:
+    /// `benchmark,member,input,iteration,<unit>,status`.
+    Iterations,
+    /// One row per (benchmark, input) pair:
+    /// `benchmark,member,input,iterations,min <unit>,avg <unit>,max <unit>,status`.
+    Aggregated,
+}
+
+/// Writes results as CSV (RFC 4180 quoting, `\r\n` line endings).
+///
+/// `member` is the name of the benchmark within a suite (and is empty for
+/// single benchmarks), inputs are written with their `Debug` impls, and
+/// measurements with [`Metric::print`]. `status` is empty unless the pair
+/// failed verification or panicked.
+pub struct CsvReporter<'o, Out: Output + ?Sized, U = ()> {
+    out: OutputAdapter<'o, Out>,
+    rows: CsvRows,
+    wrote_header: bool,
+    benchmark: &'static str,
+    in_suite: bool,
+    input: Option<(usize, String)>,
+    failure: Option<&'static str>,
+    // The (benchmark, input) pair being aggregated, with `CsvRows::Aggregated`.
+    pending: Option<(usize, &'static str, Stats<U>)>,
+}
+
+#[allow(clippy::needless_lifetimes)]
+impl CsvReporter<'static, Void> {
+    pub fn new<'o, U, O: Output>(out: &'o mut O, rows: CsvRows) -> CsvReporter<'o, O, U> {
+        CsvReporter::with_output(out, rows)
+    }
+
+    pub fn new_with_fmt_write<'o, Fw: fmt::Write, U>(
+        out: &'o mut Fw,
+        rows: CsvRows,
+    ) -> CsvReporter<'o, dyn fmt::Write + 'o, U> {
+        CsvReporter::with_output(out, rows)
+    }
+
+    pub fn new_with_io_write<'o, Iw: std::io::Write, U>(
+        out: &'o mut Iw,
+        rows: CsvRows,
+    ) -> CsvReporter<'o, dyn std::io::Write + 'o, U> {
+        CsvReporter::with_output(out, rows)
+    }
+}
+
+impl<'o, O: Output + ?Sized, U> CsvReporter<'o, O, U> {
+    fn with_output(out: &'o mut O, rows: CsvRows) -> Self {
+        CsvReporter {
+            out: OutputAdapter::new(out),
+            rows,
+            wrote_header: false,
+            benchmark: "",
+            in_suite: false,
+            input: None,
+            failure: None,
+            pending: None,
+        }
+    }
+
+    fn field(&mut self, v: &dyn Display) {
+        let mut s = String::new();
+        let _ = write!(s, "{}", v);
+        if s.contains([',', '"', '\r', '\n']) {
+            write!(self.out, "\"{}\"", s.replace('"', "\"\""));
+        } else {
+            self.out.write_str(&s);
+        }
+    }
+
+    fn header(&mut self, unit: &'static str) {
+        if self.wrote_header {
+            return;
+        }
+        self.wrote_header = true;
+
+        self.out.write_str("benchmark,member,input,");
+        match self.rows {
+            CsvRows::Iterations => {
+                self.out.write_str("iteration,");
+                self.field(&unit);
+            }
+            CsvRows::Aggregated => {
+                self.out.write_str("iterations");
+                for stat in ["min", "avg", "max"] {
+                    self.out.write_char(',');
+                    self.field(&format_args!("{} {}", stat, unit));
+                }
+            }
+        }
+        self.out.write_str(",status\r\n");
+    }
+
+    // `benchmark,member,input,`
+    fn row_start(&mut self, member: &'static str) {
+        let benchmark = self.benchmark;
+        self.field(&benchmark);
+        self.out.write_char(',');
+        if self.in_suite {
+            self.field(&member);
+        }
+        self.out.write_char(',');
+        let input = self.input.take();
+        if let Some((_, ref text)) = input {
+            self.field(text);
+        }
+        self.input = input;
+        self.out.write_char(',');
+    }
+
+    fn status(&mut self, panic: Option<&str>) {
+        match (panic, self.failure.take()) {
+            (Some(msg), _) => self.field(&format_args!("panicked: {}", msg)),
+            (None, Some(msg)) => self.field(&format_args!("failed verification: {}", msg)),
+            (None, None) => {}
+        }
+        self.out.write_str("\r\n");
+    }
+
+    fn set_input(&mut self, input_idx: usize, input: &dyn Debug) {
+        if !matches!(self.input, Some((idx, _)) if idx == input_idx) {
+            self.input = Some((input_idx, to_string(input)));
+        }
+    }
+}
+
+impl<'o, O: Output + ?Sized, U> CsvReporter<'o, O, U>
+where
+    U: Copy + Ord + core::ops::Add<Output = U>,
+{
+    fn flush_pending<M: Metric<Unit = U>>(&mut self, panic: Option<&str>)
+    where
+        U: core::ops::Div<M::Divisor, Output = U>,
+    {
+        if let Some((_, member, stats)) = self.pending.take() {
+            self.row_start(member);
+            write!(self.out, "{},", stats.count);
+            for u in [stats.min, stats.avg::<M>(), stats.max] {
+                self.field(&MetricFmtAdapter::<M>(&u));
+                self.out.write_char(',');
+            }
+            self.status(panic);
+        }
+    }
+
+    fn run<M: Metric<Unit = U>>(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        member: &'static str,
+        iteration_idx: usize,
+        measurement: U,
+    ) where
+        U: core::ops::Div<M::Divisor, Output = U>,
+    {
+        match self.rows {
+            CsvRows::Iterations => {
+                self.set_input(input_idx, input);
+                self.row_start(member);
+                write!(self.out, "{},", iteration_idx);
+                self.field(&MetricFmtAdapter::<M>(&measurement));
+                self.out.write_char(',');
+                self.status(None);
+            }
+            CsvRows::Aggregated => match &mut self.pending {
+                Some((idx, name, stats)) if *idx == input_idx && *name == member => {
+                    stats.add(measurement)
+                }
+                _ => {
+                    // The failure (if any) belongs to the new pair; hold on
+                    // to it while the previous pair is written out.
+                    let failure = self.failure.take();
+                    self.flush_pending::<M>(None);
+                    self.failure = failure;
+
+                    self.set_input(input_idx, input);
+                    self.pending = Some((input_idx, member, Stats::new(measurement)));
+                }
+            },
+        }
+    }
+}
+
+impl<'o, O, M> Reporter<M> for CsvReporter<'o, O, M::Unit>
+where
+    O: Output + ?Sized,
+    M: Metric,
+{
+    type Error = O::Error;
+
+    fn top_level_benchmarks<I: Iterator<Item = &'static str> + Clone>(&mut self, _names: I) {
+        self.header(M::UNIT_NAME);
+    }
+
+    fn starting_single_benchmark(
+        &mut self,
+        name: &'static str,
+        _inputs_size_hint: (usize, Option<usize>),
+        _iterations: usize,
+    ) {
+        self.header(M::UNIT_NAME);
+        self.benchmark = name;
+        self.in_suite = false;
+        self.input = None;
+    }
+
+    fn single_benchmark_run(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        iteration_idx: usize,
+        measurement: M::Unit,
+    ) {
+        self.run::<M>(input_idx, input, "", iteration_idx, measurement)
+    }
+
+    fn ending_single_benchmark(&mut self, _name: &'static str) {
+        self.flush_pending::<M>(None);
+    }
+
+    fn starting_new_benchmark_suite<I: Iterator<Item = (&'static str, usize)> + Clone>(
+        &mut self,
+        name: &'static str,
+        _inputs_size_hint: (usize, Option<usize>),
+        _benchmarks: I,
+        _axes: &[Axis<'_>],
+    ) {
+        self.header(M::UNIT_NAME);
+        self.benchmark = name;
+        self.in_suite = true;
+        self.input = None;
+    }
+
+    fn suite_benchmark_run(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        _benchmark_idx: usize,
+        benchmark_name: &'static str,
+        iteration_idx: usize,
+        _num_iterations: usize,
+        measurement: M::Unit,
+    ) {
+        self.run::<M>(input_idx, input, benchmark_name, iteration_idx, measurement)
+    }
+
+    fn ending_benchmark_suite(&mut self, _name: &'static str) {
+        self.flush_pending::<M>(None);
+    }
+
+    fn verification_failed(
+        &mut self,
+        _input_idx: usize,
+        _input: &dyn Debug,
+        _benchmark_name: &'static str,
+        _iteration_idx: usize,
+        message: &'static str,
+    ) {
+        self.failure = Some(message);
+    }
+
+    fn benchmark_panicked(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_name: &'static str,
+        message: &str,
+    ) {
+        let member = if self.in_suite { benchmark_name } else { "" };
+        if matches!(self.pending, Some((idx, name, _)) if idx == input_idx && name == member) {
+            return self.flush_pending::<M>(Some(message));
+        }
+        self.flush_pending::<M>(None);
+
+        // No measurements for this pair (in this mode); leave those columns
+        // empty.
+        self.set_input(input_idx, input);
+        self.row_start(member);
+        let empty = match self.rows {
+            CsvRows::Iterations => ",,",
+            CsvRows::Aggregated => "0,,,,",
+        };
+        self.out.write_str(empty);
+        self.status(Some(message));
+    }
+
+    fn ended(&mut self) {
+        self.flush_pending::<M>(None);
+        self.out.flush();
+    }
+
+    fn take_error(&mut self) -> Option<Self::Error> {
+        self.out.take_error()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Writes a GitHub-flavored Markdown table for each top level benchmark.
+///
+/// Suites get one row per input and one column per suite member; single
+/// benchmarks get one row per input and a single column. Each cell is the
+/// average ± range of the pair's measurements; failed verifications and
+/// panics are marked with ✗ and listed under the table.
+pub struct MarkdownReporter<'o, Out: Output + ?Sized, U = ()> {
+    out: OutputAdapter<'o, Out>,
+    // The current row's input; its cells are held alongside the column names.
+    row: Option<(usize, String)>,
+    columns: Vec<(&'static str, Option<String>)>,
+    // Iterations per input, for single benchmarks (suites say per run).
+    single_iterations: Option<usize>,
+    stats: Option<(usize, Stats<U>)>,
+    failure: Option<&'static str>,
+    notes: Vec<String>,
+}
+
+#[allow(clippy::needless_lifetimes)]
+impl MarkdownReporter<'static, Void> {
+    pub fn new<'o, U, O: Output>(out: &'o mut O) -> MarkdownReporter<'o, O, U> {
+        MarkdownReporter::with_output(out)
+    }
+
+    pub fn new_with_fmt_write<'o, Fw: fmt::Write, U>(
+        out: &'o mut Fw,
+    ) -> MarkdownReporter<'o, dyn fmt::Write + 'o, U> {
+        MarkdownReporter::with_output(out)
+    }
+
+    pub fn new_with_io_write<'o, Iw: std::io::Write, U>(
+        out: &'o mut Iw,
+    ) -> MarkdownReporter<'o, dyn std::io::Write + 'o, U> {
+        MarkdownReporter::with_output(out)
+    }
+}
+
+// Pipes would end the cell early; newlines would end the row.
+fn escape(s: &str) -> String {
+    s.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+impl<'o, O: Output + ?Sized, U> MarkdownReporter<'o, O, U> {
+    fn with_output(out: &'o mut O) -> Self {
+        MarkdownReporter {
+            out: OutputAdapter::new(out),
+            row: None,
+            columns: Vec::new(),
+            single_iterations: None,
+            stats: None,
+            failure: None,
+            notes: Vec::new(),
+        }
+    }
+
+    fn start_table(
+        &mut self,
+        name: &str,
+        unit: &str,
+        columns: impl Iterator<Item = &'static str> + Clone,
+    ) {
+        write!(
+            self.out,
+            "### {}\r\n\r\n_average ± range, measuring {}_\r\n\r\n| input |",
+            escape(name),
+            unit,
+        );
+        for c in columns.clone() {
+            write!(self.out, " {} |", escape(c));
+        }
+        self.out.write_str("\r\n| --- |");
+        for _ in columns.clone() {
+            self.out.write_str(" ---: |");
+        }
+        self.out.write_str("\r\n");
+
+        self.columns = columns.map(|c| (c, None)).collect();
+        self.row = None;
+        self.stats = None;
+        self.failure = None;
+    }
+
+    fn start_row(&mut self, input_idx: usize, input: &dyn Debug) {
+        if !matches!(self.row, Some((idx, _)) if idx == input_idx) {
+            self.end_row();
+            self.row = Some((input_idx, escape(&to_string(input))));
+        }
+    }
+
+    fn end_row(&mut self) {
+        if let Some((_, input)) = self.row.take() {
+            write!(self.out, "| {} |", input);
+            for (_, cell) in self.columns.iter_mut() {
+                match cell.take() {
+                    Some(c) => write!(self.out, " {} |", c),
+                    None => self.out.write_str(" - |"),
+                }
+            }
+            self.out.write_str("\r\n");
+        }
+    }
+
+    fn end_table(&mut self) {
+        self.end_row();
+        if !self.notes.is_empty() {
+            self.out.write_str("\r\n");
+            for note in self.notes.drain(..) {
+                write!(self.out, "- ✗ {}\r\n", note);
+            }
+        }
+        self.out.write_str("\r\n");
+    }
+
+    fn note(&mut self, column: usize, what: fmt::Arguments<'_>) {
+        let input = self.row.as_ref().map(|(_, i)| &**i).unwrap_or_default();
+        self.notes.push(format!(
+            "`{}` on `{}`: {}",
+            escape(self.columns[column].0),
+            input,
+            what
+        ));
+    }
+}
+
+impl<'o, O: Output + ?Sized, U> MarkdownReporter<'o, O, U>
+where
+    U: Copy + Ord + core::ops::Add<Output = U> + core::ops::Sub<Output = U>,
+{
+    fn run<M: Metric<Unit = U>>(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        column: usize,
+        iteration_idx: usize,
+        num_iterations: usize,
+        measurement: U,
+    ) where
+        U: core::ops::Div<M::Divisor, Output = U>,
+    {
+        self.start_row(input_idx, input);
+        match &mut self.stats {
+            Some((col, stats)) if *col == column && iteration_idx != 0 => stats.add(measurement),
+            _ => self.stats = Some((column, Stats::new(measurement))),
+        }
+
+        if iteration_idx + 1 == num_iterations {
+            let (_, stats) = self.stats.take().unwrap();
+            let avg = stats.avg::<M>();
+            let range = (stats.max - avg).max(avg - stats.min);
+            let failed = self.failure.take();
+
+            self.columns[column].1 = Some(format!(
+                "{}{} ± {}",
+                if failed.is_some() { "✗ " } else { "" },
+                escape(&format!("{}", MetricFmtAdapter::<M>(&avg))),
+                escape(&format!("{}", MetricFmtAdapter::<M>(&range))),
+            ));
+            if let Some(msg) = failed {
+                self.note(
+                    column,
+                    format_args!("failed verification: {}", escape(msg)),
+                );
+            }
+        }
+    }
+}
+
+impl<'o, O, M> Reporter<M> for MarkdownReporter<'o, O, M::Unit>
+where
+    O: Output + ?Sized,
+    M: Metric,
+{
+    type Error = O::Error;
+
+    fn starting_single_benchmark(
+        &mut self,
+        name: &'static str,
+        _inputs_size_hint: (usize, Option<usize>),
+        iterations: usize,
+    ) {
+        self.start_table(name, M::UNIT_NAME, [name].into_iter());
+        self.single_iterations = Some(iterations);
+    }
+
+    fn single_benchmark_iterations(&mut self, _input_idx: usize, iterations: usize) {
+        self.single_iterations = Some(iterations);
+    }
+
+    fn single_benchmark_run(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        iteration_idx: usize,
+        measurement: M::Unit,
+    ) {
+        let iterations = self.single_iterations.unwrap_or(1);
+        self.run::<M>(input_idx, input, 0, iteration_idx, iterations, measurement)
+    }
+
+    fn ending_single_benchmark(&mut self, _name: &'static str) {
+        self.end_table();
+    }
+
+    fn starting_new_benchmark_suite<I: Iterator<Item = (&'static str, usize)> + Clone>(
+        &mut self,
+        name: &'static str,
+        _inputs_size_hint: (usize, Option<usize>),
+        benchmarks: I,
+        _axes: &[Axis<'_>],
+    ) {
+        self.start_table(name, M::UNIT_NAME, benchmarks.map(|(n, _)| n));
+        self.single_iterations = None;
+    }
+
+    fn suite_benchmark_run(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_idx: usize,
+        _benchmark_name: &'static str,
+        iteration_idx: usize,
+        num_iterations: usize,
+        measurement: M::Unit,
+    ) {
+        self.run::<M>(
+            input_idx,
+            input,
+            benchmark_idx,
+            iteration_idx,
+            num_iterations,
+            measurement,
+        )
+    }
+
+    fn ending_benchmark_suite(&mut self, _name: &'static str) {
+        self.end_table();
+    }
+
+    fn verification_failed(
+        &mut self,
+        _input_idx: usize,
+        _input: &dyn Debug,
+        _benchmark_name: &'static str,
+        _iteration_idx: usize,
+        message: &'static str,
+    ) {
+        self.failure = Some(message);
+    }
+
+    fn benchmark_panicked(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_name: &'static str,
+        message: &str,
+    ) {
+        self.start_row(input_idx, input);
+        let column = match self.single_iterations {
+            Some(_) => Some(0),
+            None => self.columns.iter().position(|(n, _)| *n == benchmark_name),
+        };
+
+        self.stats = None;
+        self.failure = None;
+        if let Some(column) = column {
+            self.columns[column].1 = Some(String::from("✗ panicked"));
+            self.note(column, format_args!("panicked: {}", escape(message)));
+        }
+    }
+
+    fn ended(&mut self) {
+        self.out.flush();
+    }
+
+    fn take_error(&mut self) -> Option<Self::Error> {
+        self.out.take_error()
+    }
+}
@@ -0,0 +1,271 @@
+use core::fmt::{self, Debug, Display};
+
+use super::Reporter;
+use crate::{runner::Axis, Metric};
+
+/// The error from a pair of reporters: a tee (`(A, B)`) or a
+/// [`ProgressReporter`](super::ProgressReporter) and the reporter it wraps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EitherError<A, B> {
+    First(A),
+    Second(B),
+}
+
+impl<A: Display, B: Display> Display for EitherError<A, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EitherError::First(a) => a.fmt(f),
+            EitherError::Second(b) => b.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A: std::error::Error, B: std::error::Error> std::error::Error for EitherError<A, B> {}
+
+// Forwards every call to `A` and then to `B`; nest tuples for more than two
+// reporters, i.e. `(a, (b, c))`.
+impl<M, A, B> Reporter<M> for (A, B)
+where
+    M: Metric,
+    A: Reporter<M>,
+    B: Reporter<M>,
+{
+    type Error = EitherError<A::Error, B::Error>;
+
+    fn top_level_benchmarks<I: Iterator<Item = &'static str> + Clone>(&mut self, names: I) {
+        self.0.top_level_benchmarks(names.clone());
+        self.1.top_level_benchmarks(names);
+    }
+
+    fn starting_single_benchmark(
+        &mut self,
+        name: &'static str,
+        inputs_size_hint: (usize, Option<usize>),
+        iterations: usize,
+    ) {
+        self.0
+            .starting_single_benchmark(name, inputs_size_hint, iterations);
+        self.1
+            .starting_single_benchmark(name, inputs_size_hint, iterations);
+    }
+
+    fn single_benchmark_iterations(&mut self, input_idx: usize, iterations: usize) {
+        self.0.single_benchmark_iterations(input_idx, iterations);
+        self.1.single_benchmark_iterations(input_idx, iterations);
+    }
+
+    fn single_benchmark_run(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        iteration_idx: usize,
+        measurement: M::Unit,
+    ) {
+        self.0
+            .single_benchmark_run(input_idx, input, iteration_idx, measurement);
+        self.1
+            .single_benchmark_run(input_idx, input, iteration_idx, measurement);
+    }
+
+    fn ending_single_benchmark(&mut self, name: &'static str) {
+        self.0.ending_single_benchmark(name);
+        self.1.ending_single_benchmark(name);
+    }
+
+    fn starting_new_benchmark_suite<I: Iterator<Item = (&'static str, usize)> + Clone>(
+        &mut self,
+        name: &'static str,
+        inputs_size_hint: (usize, Option<usize>),
+        benchmarks: I,
+        axes: &[Axis<'_>],
+    ) {
+        self.0
+            .starting_new_benchmark_suite(name, inputs_size_hint, benchmarks.clone(), axes);
+        self.1
+            .starting_new_benchmark_suite(name, inputs_size_hint, benchmarks, axes);
+    }
+
+    fn suite_input_axes(&mut self, input_idx: usize, axes: &[Axis<'_>]) {
+        self.0.suite_input_axes(input_idx, axes);
+        self.1.suite_input_axes(input_idx, axes);
+    }
+
+    fn suite_benchmark_run(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_idx: usize,
+        benchmark_name: &'static str,
+        iteration_idx: usize,
+        num_iterations: usize,
+        measurement: M::Unit,
+    ) {
+        self.0.suite_benchmark_run(
+            input_idx,
+            input,
+            benchmark_idx,
+            benchmark_name,
+            iteration_idx,
+            num_iterations,
+            measurement,
+        );
+        self.1.suite_benchmark_run(
+            input_idx,
+            input,
+            benchmark_idx,
+            benchmark_name,
+            iteration_idx,
+            num_iterations,
+            measurement,
+        );
+    }
+
+    fn ending_benchmark_suite(&mut self, name: &'static str) {
+        self.0.ending_benchmark_suite(name);
+        self.1.ending_benchmark_suite(name);
+    }
+
+    fn verification_failed(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_name: &'static str,
+        iteration_idx: usize,
+        message: &'static str,
+    ) {
+        self.0
+            .verification_failed(input_idx, input, benchmark_name, iteration_idx, message);
+        self.1
+            .verification_failed(input_idx, input, benchmark_name, iteration_idx, message);
+    }
+
+    fn benchmark_panicked(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_name: &'static str,
+        message: &str,
+    ) {
+        self.0
+            .benchmark_panicked(input_idx, input, benchmark_name, message);
+        self.1
+            .benchmark_panicked(input_idx, input, benchmark_name, message);
+    }
+
+    fn ended(&mut self) {
+        self.0.ended();
+        self.1.ended();
+    }
+
+    // If both failed, `A`'s error is the one that's kept.
+    fn take_error(&mut self) -> Option<Self::Error> {
+        let (a, b) = (self.0.take_error(), self.1.take_error());
+        a.map(EitherError::First)
+            .or_else(|| b.map(EitherError::Second))
+    }
+}
+
+// So reporters can be borrowed into a tee (and used again afterwards).
+impl<M: Metric, R: Reporter<M> + ?Sized> Reporter<M> for &mut R {
+    type Error = R::Error;
+
+    fn top_level_benchmarks<I: Iterator<Item = &'static str> + Clone>(&mut self, names: I) {
+        (**self).top_level_benchmarks(names)
+    }
+
+    fn starting_single_benchmark(
+        &mut self,
+        name: &'static str,
+        inputs_size_hint: (usize, Option<usize>),
+        iterations: usize,
+    ) {
+        (**self).starting_single_benchmark(name, inputs_size_hint, iterations)
+    }
+
+    fn single_benchmark_iterations(&mut self, input_idx: usize, iterations: usize) {
+        (**self).single_benchmark_iterations(input_idx, iterations)
+    }
+
+    fn single_benchmark_run(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        iteration_idx: usize,
+        measurement: M::Unit,
+    ) {
+        (**self).single_benchmark_run(input_idx, input, iteration_idx, measurement)
+    }
+
+    fn ending_single_benchmark(&mut self, name: &'static str) {
+        (**self).ending_single_benchmark(name)
+    }
+
+    fn starting_new_benchmark_suite<I: Iterator<Item = (&'static str, usize)> + Clone>(
+        &mut self,
+        name: &'static str,
+        inputs_size_hint: (usize, Option<usize>),
+        benchmarks: I,
+        axes: &[Axis<'_>],
+    ) {
+        (**self).starting_new_benchmark_suite(name, inputs_size_hint, benchmarks, axes)
+    }
+
+    fn suite_input_axes(&mut self, input_idx: usize, axes: &[Axis<'_>]) {
+        (**self).suite_input_axes(input_idx, axes)
+    }
+
+    fn suite_benchmark_run(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_idx: usize,
+        benchmark_name: &'static str,
+        iteration_idx: usize,
+        num_iterations: usize,
+        measurement: M::Unit,
+    ) {
+        (**self).suite_benchmark_run(
+            input_idx,
+            input,
+            benchmark_idx,
+            benchmark_name,
+            iteration_idx,
+            num_iterations,
+            measurement,
+        )
+    }
+
+    fn ending_benchmark_suite(&mut self, name: &'static str) {
+        (**self).ending_benchmark_suite(name)
+    }
+
+    fn verification_failed(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_name: &'static str,
+        iteration_idx: usize,
+        message: &'static str,
+    ) {
+        (**self).verification_failed(input_idx, input, benchmark_name, iteration_idx, message)
+    }
+
+    fn benchmark_panicked(
+        &mut self,
+        input_idx: usize,
+        input: &dyn Debug,
+        benchmark_name: &'static str,
+        message: &str,
+    ) {
+        (**self).benchmark_panicked(input_idx, input, benchmark_name, message)
+    }
+
+    fn ended(&mut self) {
+        (**self).ended()
+    }
+
+    fn take_error(&mut self) -> Option<R::Error> {
+        (**self).take_error()
+    }
+}
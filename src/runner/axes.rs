@@ -0,0 +1,298 @@
+use core::{
+    fmt::{self, Debug},
+    marker::PhantomData,
+};
+
+use super::suite::Suite;
+
+/// Starts a set of named input axes; add more axes with [`Axes::axis`] and
+/// pass the result to [`matrix`](super::matrix).
+///
+/// Benchmarks in the resulting suite are run on every combination of the
+/// axes' values (i.e. the cartesian product), as a tuple with one element
+/// per axis: `(A, B)`, `(A, B, C)`, etc.
+///
+/// Axes are iterated in order, with the _last_ axis varying the fastest.
+///
+/// Axis sources are cloned and re-iterated as the product is traversed so
+/// they should be cheap to clone (i.e. ranges, arrays, iterator adapters).
+pub fn build_axes<I>(name: &'static str, values: I) -> Axes<(Cursor<I>,)>
+where
+    I: IntoIterator + Clone,
+    I::Item: Clone + Debug,
+{
+    Axes {
+        axes: (Cursor::new(name, values),),
+    }
+}
+
+/// Makes a benchmark suite whose inputs are the cartesian product of `axes`.
+///
+/// This is just like [`suite`](super::suite) except that reporters are also
+/// told which value of each axis each input corresponds to (see
+/// [`Reporter::suite_input_axes`](crate::Reporter::suite_input_axes)); this
+/// lets them lay results out as a grid.
+pub fn build_matrix<T>(name: &'static str, axes: Axes<T>) -> Suite<T::Item, Axes<T>, ()>
+where
+    T: AxisList,
+{
+    Suite::new(name, axes, Some(AxesIter::<T>::with_axes))
+}
+
+/// A set of named input axes; see [`axes`](super::axes).
+#[derive(Debug, Clone)]
+pub struct Axes<T> {
+    axes: T,
+}
+
+/// Describes one axis of a [`matrix`](super::matrix) suite to a
+/// [`Reporter`](crate::Reporter).
+#[derive(Clone, Copy)]
+pub struct Axis<'a> {
+    pub name: &'static str,
+    pub len: usize,
+    values: &'a dyn AxisValues,
+}
+
+impl<'a> Axis<'a> {
+    // For reporters that replay axes (the binary decoder and the
+    // aggregating reporter).
+    pub(crate) fn new(name: &'static str, len: usize, values: &'a dyn AxisValues) -> Self {
+        Axis { name, len, values }
+    }
+
+    /// Returns the value at `idx` along this axis, for printing.
+    pub fn value(&self, idx: usize) -> impl Debug + 'a {
+        AxisValue(self.values, idx)
+    }
+
+    /// Given the index of an input within a [`matrix`](super::matrix) suite,
+    /// returns the index of the input's value along `axes[axis_idx]`.
+    pub fn coordinate(axes: &[Axis<'_>], input_idx: usize, axis_idx: usize) -> usize {
+        let stride: usize = axes[axis_idx + 1..].iter().map(|a| a.len).product();
+        (input_idx / stride.max(1)) % axes[axis_idx].len.max(1)
+    }
+}
+
+impl Debug for Axis<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Axis")
+            .field("name", &self.name)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+struct AxisValue<'a>(&'a dyn AxisValues, usize);
+impl Debug for AxisValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt_value(self.1, f)
+    }
+}
+
+pub(crate) trait AxisValues {
+    fn fmt_value(&self, idx: usize, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct Cursor<I: IntoIterator> {
+    name: &'static str,
+    src: I,
+    iter: Option<I::IntoIter>,
+    current: Option<I::Item>,
+}
+
+impl<I> Cursor<I>
+where
+    I: IntoIterator + Clone,
+    I::Item: Clone + Debug,
+{
+    fn new(name: &'static str, src: I) -> Self {
+        Cursor {
+            name,
+            src,
+            iter: None,
+            current: None,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.src.clone().into_iter().count()
+    }
+
+    // Starts back at the first value; returns `false` if the axis is empty.
+    fn reset(&mut self) -> bool {
+        let mut iter = self.src.clone().into_iter();
+        self.current = iter.next();
+        self.iter = Some(iter);
+
+        self.current.is_some()
+    }
+
+    // Moves to the next value; returns `false` if there isn't one.
+    fn step(&mut self) -> bool {
+        self.current = self.iter.as_mut().and_then(Iterator::next);
+        self.current.is_some()
+    }
+
+    fn current(&self) -> I::Item {
+        self.current.clone().unwrap()
+    }
+}
+
+impl<I> AxisValues for Cursor<I>
+where
+    I: IntoIterator + Clone,
+    I::Item: Debug,
+{
+    fn fmt_value(&self, idx: usize, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.src.clone().into_iter().nth(idx) {
+            Some(v) => Debug::fmt(&v, f),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Implemented for the (tuples of) axes that [`Axes`] can hold.
+pub trait AxisList {
+    type Item: Debug;
+
+    #[doc(hidden)]
+    fn num_combinations(&self) -> usize;
+    #[doc(hidden)]
+    fn with_axes(&self, f: &mut dyn FnMut(&[Axis<'_>]));
+    #[doc(hidden)]
+    fn start(&mut self) -> bool;
+    #[doc(hidden)]
+    fn advance(&mut self) -> bool;
+    #[doc(hidden)]
+    fn current(&self) -> Self::Item;
+}
+
+/// Iterator over the cartesian product of some [`Axes`].
+pub struct AxesIter<T: AxisList> {
+    axes: T,
+    started: bool,
+    remaining: usize,
+    _p: PhantomData<T::Item>,
+}
+
+impl<T: AxisList> AxesIter<T> {
+    fn with_axes(&self, f: &mut dyn FnMut(&[Axis<'_>])) {
+        self.axes.with_axes(f)
+    }
+}
+
+impl<T: AxisList> Iterator for AxesIter<T> {
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<T::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let ok = if self.started {
+            self.axes.advance()
+        } else {
+            self.started = true;
+            self.axes.start()
+        };
+
+        if ok {
+            self.remaining -= 1;
+            Some(self.axes.current())
+        } else {
+            self.remaining = 0;
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T: AxisList> IntoIterator for Axes<T> {
+    type Item = T::Item;
+    type IntoIter = AxesIter<T>;
+
+    fn into_iter(self) -> AxesIter<T> {
+        AxesIter {
+            remaining: self.axes.num_combinations(),
+            axes: self.axes,
+            started: false,
+            _p: PhantomData,
+        }
+    }
+}
+
+macro_rules! axes {
+    ($($ty:ident $idx:tt),+ ; rev: $($rev:tt),+ ; next: $next:ident) => {
+        axes!($($ty $idx),+ ; rev: $($rev),+);
+
+        impl<$($ty),+> Axes<($(Cursor<$ty>,)+)>
+        where
+            $($ty: IntoIterator + Clone, $ty::Item: Clone + Debug,)+
+        {
+            pub fn axis<$next>(
+                self,
+                name: &'static str,
+                values: $next,
+            ) -> Axes<($(Cursor<$ty>,)+ Cursor<$next>)>
+            where
+                $next: IntoIterator + Clone,
+                $next::Item: Clone + Debug,
+            {
+                Axes {
+                    axes: ($(self.axes.$idx,)+ Cursor::new(name, values)),
+                }
+            }
+        }
+    };
+
+    ($($ty:ident $idx:tt),+ ; rev: $($rev:tt),+) => {
+        impl<$($ty),+> AxisList for ($(Cursor<$ty>,)+)
+        where
+            $($ty: IntoIterator + Clone, $ty::Item: Clone + Debug,)+
+        {
+            type Item = ($($ty::Item,)+);
+
+            fn num_combinations(&self) -> usize {
+                1 $(* self.$idx.len())+
+            }
+
+            fn with_axes(&self, f: &mut dyn FnMut(&[Axis<'_>])) {
+                f(&[$(
+                    Axis { name: self.$idx.name, len: self.$idx.len(), values: &self.$idx },
+                )+])
+            }
+
+            fn start(&mut self) -> bool {
+                true $(&& self.$idx.reset())+
+            }
+
+            // Odometer style: step the last axis, wrapping around into the
+            // axes before it.
+            fn advance(&mut self) -> bool {
+                $(
+                    if self.$rev.step() {
+                        return true;
+                    }
+                    self.$rev.reset();
+                )+
+
+                false
+            }
+
+            fn current(&self) -> Self::Item {
+                ($(self.$idx.current(),)+)
+            }
+        }
+    };
+}
+
+axes!(A 0 ; rev: 0 ; next: B);
+axes!(A 0, B 1 ; rev: 1, 0 ; next: C);
+axes!(A 0, B 1, C 2 ; rev: 2, 1, 0 ; next: D);
+axes!(A 0, B 1, C 2, D 3 ; rev: 3, 2, 1, 0);
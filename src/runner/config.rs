@@ -0,0 +1,179 @@
+/// Settings for how a benchmark is run.
+///
+/// A [`BenchmarkRunner`](super::BenchmarkRunner) has defaults for all of
+/// these; [`single`](super::single) benchmarks, [`suite`](super::suite)s, and
+/// individual suite members can override any of them with `with_config`
+/// (or [`Suite::add_with_config`](super::Suite::add_with_config)).
+///
+/// Overrides are resolved from the most specific to the least specific:
+/// suite member, then suite (or single benchmark), then runner.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    iterations: Option<usize>,
+    warmup: Option<usize>,
+    budget: Option<u64>,
+    batch_size: Option<BatchSize>,
+    #[cfg(feature = "std")]
+    catch_panics: Option<bool>,
+}
+
+/// How many times a benchmark is run between each pair of
+/// [`Metric::start`](crate::Metric::start) and
+/// [`Metric::end`](crate::Metric::end) calls.
+///
+/// Batching is useful for benchmarks that take less time than the resolution
+/// of the [`Metric`](crate::Metric) being used (i.e. a few instructions
+/// measured with a millisecond timer). The measurement for a batch is divided
+/// by the batch size (using [`Metric::Divisor`](crate::Metric::Divisor)) so
+/// reporters still see per-run measurements; batch sizes that don't fit in a
+/// `Divisor` are lowered to the largest one that does.
+///
+/// Within a batch, [`Benchmark::setup`](crate::Benchmark::setup) is called
+/// once before the batch and [`Benchmark::teardown`](crate::Benchmark::teardown)
+/// is called once after with the result of the last run; the results of the
+/// other runs are dropped while the measurement is running. Benchmarks that
+/// need a fresh `setup` for every run (i.e. [`prepared`](crate::prepared)
+/// ones; see [`Benchmark::BATCHABLE`](crate::Benchmark::BATCHABLE)) are not
+/// batched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchSize {
+    /// Run the benchmark this many times per measurement.
+    Fixed(usize),
+    /// Pick a batch size for each input by doubling the batch size until a
+    /// batch's measurement is much larger than that of an empty measurement
+    /// (up to [`BatchSize::MAX_AUTO`] runs per batch).
+    ///
+    /// If the measurement stops growing as the batch size doubles (i.e. with
+    /// a metric that always reads the same value) the batch size it last
+    /// grew at is used instead. The runs made while picking a batch size
+    /// aren't verified (or reported).
+    Auto,
+}
+
+impl BatchSize {
+    /// The largest batch size [`BatchSize::Auto`] will pick.
+    pub const MAX_AUTO: usize = 1 << 16;
+}
+
+impl Config {
+    /// A `Config` that doesn't override anything.
+    pub const fn new() -> Self {
+        Config {
+            iterations: None,
+            warmup: None,
+            budget: None,
+            batch_size: None,
+            #[cfg(feature = "std")]
+            catch_panics: None,
+        }
+    }
+
+    /// Number of measured iterations to run for each input.
+    ///
+    /// Must be at least 1.
+    pub const fn iterations(mut self, iterations: usize) -> Self {
+        assert!(
+            iterations > 0,
+            "benchmarks must run for at least 1 iteration"
+        );
+        self.iterations = Some(iterations);
+        self
+    }
+
+    /// Number of unmeasured iterations to run for each input before the
+    /// measured iterations.
+    pub const fn warmup(mut self, iterations: usize) -> Self {
+        self.warmup = Some(iterations);
+        self
+    }
+
+    /// Caps the total of each input's measurements at roughly `units` (in the
+    /// [`Metric`](crate::Metric)'s unit, as a [`RawUnit`](crate::metrics::RawUnit);
+    /// nanoseconds for `StdSysTime`).
+    ///
+    /// Before the measured iterations for an input, one (unreported) run is
+    /// measured and the input gets as many iterations as fit in the budget:
+    /// at least 1 and at most [`iterations`](Config::iterations). This lets
+    /// slow and fast benchmarks share a runner: give both a large iteration
+    /// count and the slow ones will run fewer of them.
+    pub const fn budget(mut self, units: u64) -> Self {
+        self.budget = Some(units);
+        self
+    }
+
+    /// Number of times to run the benchmark per measurement; see
+    /// [`BatchSize`].
+    pub const fn batch_size(mut self, batch_size: BatchSize) -> Self {
+        if let BatchSize::Fixed(n) = batch_size {
+            assert!(n > 0, "batches must contain at least 1 run");
+        }
+        self.batch_size = Some(batch_size);
+        self
+    }
+
+    /// Whether to keep going when the benchmark panics.
+    ///
+    /// When enabled, each (benchmark, input) pair is run under
+    /// [`catch_unwind`](std::panic::catch_unwind); panics are handed to the
+    /// [`Reporter`](crate::Reporter) (see
+    /// [`Reporter::benchmark_panicked`](crate::Reporter::benchmark_panicked))
+    /// and the runner moves on to the next pair. The default panic hook still
+    /// runs (and prints the panic).
+    ///
+    /// Benchmarks are assumed to be usable after unwinding; the same
+    /// benchmark is run on the rest of the inputs.
+    #[cfg(feature = "std")]
+    #[cfg_attr(all(docs, not(doctest)), doc(cfg(feature = "std")))]
+    pub const fn catch_panics(mut self, catch: bool) -> Self {
+        self.catch_panics = Some(catch);
+        self
+    }
+
+    /// Fills in the settings this `Config` does not override from `fallback`.
+    pub const fn or(self, fallback: Config) -> Config {
+        Config {
+            iterations: match self.iterations {
+                Some(it) => Some(it),
+                None => fallback.iterations,
+            },
+            warmup: match self.warmup {
+                Some(it) => Some(it),
+                None => fallback.warmup,
+            },
+            budget: match self.budget {
+                Some(b) => Some(b),
+                None => fallback.budget,
+            },
+            batch_size: match self.batch_size {
+                Some(b) => Some(b),
+                None => fallback.batch_size,
+            },
+            #[cfg(feature = "std")]
+            catch_panics: match self.catch_panics {
+                Some(c) => Some(c),
+                None => fallback.catch_panics,
+            },
+        }
+    }
+
+    pub(crate) fn num_iterations(&self) -> usize {
+        self.iterations.unwrap_or(1)
+    }
+
+    pub(crate) fn num_warmup_iterations(&self) -> usize {
+        self.warmup.unwrap_or(0)
+    }
+
+    pub(crate) fn budgeted(&self) -> Option<u64> {
+        self.budget
+    }
+
+    pub(crate) fn batching(&self) -> BatchSize {
+        self.batch_size.unwrap_or(BatchSize::Fixed(1))
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn catching_panics(&self) -> bool {
+        self.catch_panics.unwrap_or(false)
+    }
+}
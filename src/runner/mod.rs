@@ -2,40 +2,57 @@ use super::{Metric, Reporter};
 
 mod support;
 pub use support::black_box;
-use support::HListIterator;
+use support::{isolate, run_iterations, HListIterator};
+
+mod config;
+pub use config::{BatchSize, Config};
 
 mod single;
 pub use single::build_single as single;
 
 mod suite;
-pub use suite::build_suite as suite;
+pub use suite::{build_suite as suite, Suite};
+
+mod axes;
+pub(crate) use axes::AxisValues;
+pub use axes::{build_axes as axes, build_matrix as matrix, Axes, Axis};
 
 pub struct BenchmarkRunner<L: RunnableBenchmarkList = ()> {
-    iterations: usize,
+    config: Config,
     list: L,
 }
 
 impl Default for BenchmarkRunner<()> {
     fn default() -> Self {
-        Self {
-            iterations: 1,
-            list: (),
-        }
+        Self::new()
     }
 }
 
 impl BenchmarkRunner {
     pub const fn new() -> BenchmarkRunner<()> {
         BenchmarkRunner {
-            iterations: 1,
+            config: Config::new().iterations(1).warmup(0),
             list: (),
         }
     }
 }
 
 impl<L: RunnableBenchmarkList> BenchmarkRunner<L> {
+    /// Sets the default number of iterations; see [`Config::iterations`].
     pub const fn set_iterations(mut self, it: usize) -> Self {
-        self.iterations = it;
+        self.config = self.config.iterations(it);
+        self
+    }
+
+    /// Sets the default number of warmup iterations; see [`Config::warmup`].
+    pub const fn set_warmup(mut self, it: usize) -> Self {
+        self.config = self.config.warmup(it);
+        self
+    }
+
+    /// Sets the defaults for the benchmarks that do not override them.
+    pub const fn set_config(mut self, config: Config) -> Self {
+        self.config = config.or(self.config);
         self
     }
 
@@ -45,27 +62,33 @@ impl<L: RunnableBenchmarkList> BenchmarkRunner<L> {
         (X, L): RunnableBenchmarkList,
     {
         BenchmarkRunner {
-            iterations: self.iterations,
+            config: self.config,
             list: (top_level_benchmark, self.list),
         }
     }
 
-    pub fn run<M: Metric, R: Reporter<M>>(self, metric: &mut M, reporter: &mut R)
+    /// Runs every benchmark, even if `reporter` fails partway through; the
+    /// first error it ran into (i.e. a closed pipe) is returned at the end.
+    pub fn run<M: Metric, R: Reporter<M>>(
+        self,
+        metric: &mut M,
+        reporter: &mut R,
+    ) -> Result<(), R::Error>
     where
         for<'a> HListIterator<'a, dyn RunnableBenchmarkList + 'a>: Clone,
     {
         reporter.top_level_benchmarks(HListIterator(&self.list as _));
-        reporter.num_iterations(self.iterations);
 
-        self.list.run(metric, reporter, self.iterations);
+        self.list.run(metric, reporter, &self.config);
 
         reporter.ended();
+        reporter.take_error().map_or(Ok(()), Err)
     }
 }
 
 #[allow(clippy::len_without_is_empty)]
 pub trait RunnableBenchmarkList {
-    fn run<M: Metric, R: Reporter<M>>(self, m: &mut M, r: &mut R, iterations: usize)
+    fn run<M: Metric, R: Reporter<M>>(self, m: &mut M, r: &mut R, defaults: &Config)
     where
         Self: Sized;
 
@@ -101,7 +124,7 @@ impl<'a> Iterator for HListIterator<'a, (dyn RunnableBenchmarkList + 'a)> {
 }
 
 impl RunnableBenchmarkList for () {
-    fn run<M: Metric, R: Reporter<M>>(self, _m: &mut M, _r: &mut R, _iterations: usize) {}
+    fn run<M: Metric, R: Reporter<M>>(self, _m: &mut M, _r: &mut R, _defaults: &Config) {}
 
     fn name_and_next(&self) -> Option<(&'static str, &dyn RunnableBenchmarkList)> {
         None
@@ -1,6 +1,6 @@
 use core::{any::Any, fmt::Debug, marker::PhantomData};
 
-use super::{black_box, RunnableBenchmarkList};
+use super::{isolate, run_iterations, Config, RunnableBenchmarkList};
 use crate::{Benchmark, Metric, Reporter};
 
 pub fn build_single<B: Benchmark<Inp>, Inp: Any + Debug, I: IntoIterator<Item = Inp>>(
@@ -12,6 +12,7 @@ pub fn build_single<B: Benchmark<Inp>, Inp: Any + Debug, I: IntoIterator<Item =
         name,
         benchmark,
         inputs,
+        config: Config::new(),
         _p: PhantomData,
     }
 }
@@ -20,9 +21,18 @@ pub struct SingleBenchmark<B: Benchmark<Inp>, Inp: Any + Debug, I: IntoIterator<
     name: &'static str,
     benchmark: B,
     inputs: I,
+    config: Config,
     _p: PhantomData<Inp>,
 }
 
+impl<B: Benchmark<Inp>, Inp: Any + Debug, I: IntoIterator<Item = Inp>> SingleBenchmark<B, Inp, I> {
+    /// Overrides the runner's settings for this benchmark.
+    pub const fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+}
+
 impl<B, Inp, I, Rest> RunnableBenchmarkList for (SingleBenchmark<B, Inp, I>, Rest)
 where
     B: Benchmark<Inp>,
@@ -30,27 +40,40 @@ where
     I: IntoIterator<Item = Inp>,
     Rest: RunnableBenchmarkList,
 {
-    fn run<M: Metric, R: Reporter<M>>(self, m: &mut M, r: &mut R, iterations: usize) {
+    fn run<M: Metric, R: Reporter<M>>(self, m: &mut M, r: &mut R, defaults: &Config) {
         let (mut this, rest) = self;
+        let config = this.config.or(*defaults);
 
         let inputs = this.inputs.into_iter();
-        r.starting_single_benchmark(this.name, inputs.size_hint());
+        r.starting_single_benchmark(this.name, inputs.size_hint(), config.num_iterations());
 
         for (inp_idx, inp) in inputs.enumerate() {
-            for it_idx in 0..iterations {
-                this.benchmark.setup(&inp);
-                let before = m.start();
-                let res = black_box(this.benchmark.run(black_box(&inp)));
-                let measurement = m.end(before);
-                this.benchmark.teardown(&inp, res);
-
-                r.single_benchmark_run(inp_idx, &inp, it_idx, measurement);
+            let res = isolate(&config, || {
+                run_iterations(
+                    &mut this.benchmark,
+                    m,
+                    &inp,
+                    &config,
+                    |it_idx, iterations, measurement, failure| {
+                        if it_idx == 0 && config.budgeted().is_some() {
+                            r.single_benchmark_iterations(inp_idx, iterations);
+                        }
+                        if let Some(msg) = failure {
+                            r.verification_failed(inp_idx, &inp, this.name, it_idx, msg);
+                        }
+                        r.single_benchmark_run(inp_idx, &inp, it_idx, measurement)
+                    },
+                )
+            });
+
+            if let Err(panic) = res {
+                r.benchmark_panicked(inp_idx, &inp, this.name, panic.message());
             }
         }
 
         r.ending_single_benchmark(this.name);
 
-        rest.run(m, r, iterations);
+        rest.run(m, r, defaults);
     }
 
     fn name_and_next(&self) -> Option<(&'static str, &dyn RunnableBenchmarkList)> {
@@ -1,27 +1,41 @@
 use core::{any::Any, fmt::Debug, marker::PhantomData};
 
-use super::{black_box, HListIterator, RunnableBenchmarkList};
+use super::{isolate, run_iterations, Axis, Config, RunnableBenchmarkList};
 use crate::{Benchmark, Metric, Reporter};
 
 pub fn build_suite<Inp: Debug, I: IntoIterator<Item = Inp>>(
     name: &'static str,
     inputs: I,
 ) -> Suite<Inp, I, ()> {
-    Suite {
-        name,
-        benchmark_list: (),
-        inputs,
-        _p: PhantomData,
-    }
+    Suite::new(name, inputs, None)
 }
 
+// Given the inputs iterator, hands the reporter a description of the axes the
+// inputs are drawn from; only [`matrix`](super::matrix) suites have this.
+type DescribeAxes<I> = fn(&<I as IntoIterator>::IntoIter, &mut dyn FnMut(&[Axis<'_>]));
+
 pub struct Suite<Inp: Debug, I: IntoIterator<Item = Inp>, L: RunnableSuiteBenchmarkList<Inp> = ()> {
     name: &'static str,
     benchmark_list: L,
     inputs: I,
+    axes: Option<DescribeAxes<I>>,
+    config: Config,
     _p: PhantomData<Inp>,
 }
 
+impl<Inp: Debug, I: IntoIterator<Item = Inp>> Suite<Inp, I, ()> {
+    pub(super) fn new(name: &'static str, inputs: I, axes: Option<DescribeAxes<I>>) -> Self {
+        Suite {
+            name,
+            benchmark_list: (),
+            inputs,
+            axes,
+            config: Config::new(),
+            _p: PhantomData,
+        }
+    }
+}
+
 impl<Inp: Any + Debug, I: IntoIterator<Item = Inp>, L: RunnableSuiteBenchmarkList<Inp>>
     Suite<Inp, I, L>
 {
@@ -29,10 +43,22 @@ impl<Inp: Any + Debug, I: IntoIterator<Item = Inp>, L: RunnableSuiteBenchmarkLis
         self,
         name: &'static str,
         benchmark: B,
+    ) -> Suite<Inp, I, (SuiteMember<B, Inp>, L)> {
+        self.add_with_config(name, benchmark, Config::new())
+    }
+
+    /// Like [`Suite::add`] but overrides the suite's settings for this
+    /// benchmark.
+    pub fn add_with_config<B: Benchmark<Inp>>(
+        self,
+        name: &'static str,
+        benchmark: B,
+        config: Config,
     ) -> Suite<Inp, I, (SuiteMember<B, Inp>, L)> {
         let x = SuiteMember {
             name,
             benchmark,
+            config,
             _p: PhantomData,
         };
 
@@ -40,15 +66,24 @@ impl<Inp: Any + Debug, I: IntoIterator<Item = Inp>, L: RunnableSuiteBenchmarkLis
             name: self.name,
             benchmark_list: (x, self.benchmark_list),
             inputs: self.inputs,
+            axes: self.axes,
+            config: self.config,
             _p: PhantomData,
         }
     }
+
+    /// Overrides the runner's settings for the benchmarks in this suite.
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
 }
 
 #[doc(hidden)]
 pub struct SuiteMember<B: Benchmark<Inp>, Inp: Any + Debug> {
     name: &'static str,
     benchmark: B,
+    config: Config,
     _p: PhantomData<Inp>,
 }
 
@@ -62,14 +97,16 @@ pub trait RunnableSuiteBenchmarkList<Inp: Debug> {
         &mut self,
         m: &mut M,
         r: &mut R,
-        iterations: usize,
+        suite_config: &Config,
         inp_idx: usize,
         inp: &Inp,
         benchmark_idx: usize,
     ) where
         Self: Sized;
 
-    fn name_and_next(&self) -> Option<(&'static str, &dyn RunnableSuiteBenchmarkList<Inp>)>;
+    fn entry_and_next(
+        &self,
+    ) -> Option<(&'static str, Config, &dyn RunnableSuiteBenchmarkList<Inp>)>;
 
     fn len(&self) -> usize;
 }
@@ -79,13 +116,13 @@ impl<I: Debug> RunnableSuiteBenchmarkList<I> for () {
         &mut self,
         _m: &mut M,
         _r: &mut R,
-        _iterations: usize,
+        _suite_config: &Config,
         _inp_idx: usize,
         _inp: &I,
         _benchmark_idx: usize,
     ) {
     }
-    fn name_and_next(&self) -> Option<(&'static str, &dyn RunnableSuiteBenchmarkList<I>)> {
+    fn entry_and_next(&self) -> Option<(&'static str, Config, &dyn RunnableSuiteBenchmarkList<I>)> {
         None
     }
     fn len(&self) -> usize {
@@ -93,14 +130,31 @@ impl<I: Debug> RunnableSuiteBenchmarkList<I> for () {
     }
 }
 
-impl<'a, I: Debug> Iterator for HListIterator<'a, (dyn RunnableSuiteBenchmarkList<I> + 'a)> {
-    type Item = &'static str;
+/// Yields the name and the (effective) number of iterations of each benchmark
+/// in a suite.
+#[doc(hidden)]
+pub struct SuiteBenchmarks<'a, I: Debug> {
+    list: &'a dyn RunnableSuiteBenchmarkList<I>,
+    suite_config: Config,
+}
+
+impl<'a, I: Debug> Clone for SuiteBenchmarks<'a, I> {
+    fn clone(&self) -> Self {
+        Self {
+            list: self.list,
+            suite_config: self.suite_config,
+        }
+    }
+}
+
+impl<'a, I: Debug> Iterator for SuiteBenchmarks<'a, I> {
+    type Item = (&'static str, usize);
 
     fn next(&mut self) -> Option<Self::Item> {
-        let (name, next) = self.0.name_and_next()?;
-        self.0 = next;
+        let (name, config, next) = self.list.entry_and_next()?;
+        self.list = next;
 
-        Some(name)
+        Some((name, config.or(self.suite_config).num_iterations()))
     }
 }
 
@@ -114,28 +168,46 @@ where
         &mut self,
         m: &mut M,
         r: &mut R,
-        iterations: usize,
+        suite_config: &Config,
         inp_idx: usize,
         inp: &I,
         benchmark_idx: usize,
     ) {
         let (ref mut this, rest) = self;
+        let config = this.config.or(*suite_config);
 
-        for it_idx in 0..iterations {
-            this.benchmark.setup(inp);
-            let before = m.start();
-            let res = black_box(this.benchmark.run(black_box(inp)));
-            let measurement = m.end(before);
-            this.benchmark.teardown(inp, res);
+        let res = isolate(&config, || {
+            run_iterations(
+                &mut this.benchmark,
+                m,
+                inp,
+                &config,
+                |it_idx, iterations, measurement, failure| {
+                    if let Some(msg) = failure {
+                        r.verification_failed(inp_idx, inp, this.name, it_idx, msg);
+                    }
+                    r.suite_benchmark_run(
+                        inp_idx,
+                        inp,
+                        benchmark_idx,
+                        this.name,
+                        it_idx,
+                        iterations,
+                        measurement,
+                    )
+                },
+            )
+        });
 
-            r.suite_benchmark_run(inp_idx, inp, benchmark_idx, this.name, it_idx, measurement);
+        if let Err(panic) = res {
+            r.benchmark_panicked(inp_idx, inp, this.name, panic.message());
         }
 
-        rest.run(m, r, iterations, inp_idx, inp, benchmark_idx + 1);
+        rest.run(m, r, suite_config, inp_idx, inp, benchmark_idx + 1);
     }
 
-    fn name_and_next(&self) -> Option<(&'static str, &dyn RunnableSuiteBenchmarkList<I>)> {
-        Some((self.0.name, &self.1))
+    fn entry_and_next(&self) -> Option<(&'static str, Config, &dyn RunnableSuiteBenchmarkList<I>)> {
+        Some((self.0.name, self.0.config, &self.1))
     }
 
     fn len(&self) -> usize {
@@ -149,25 +221,39 @@ where
     I: IntoIterator<Item = Inp>,
     L: RunnableSuiteBenchmarkList<Inp>,
     Rest: RunnableBenchmarkList,
-    for<'a> HListIterator<'a, dyn RunnableSuiteBenchmarkList<Inp> + 'a>: Clone,
 {
-    fn run<M: Metric, R: Reporter<M>>(self, m: &mut M, r: &mut R, iterations: usize) {
+    fn run<M: Metric, R: Reporter<M>>(self, m: &mut M, r: &mut R, defaults: &Config) {
         let (mut this, rest) = self;
+        let config = this.config.or(*defaults);
+
+        let mut inputs = this.inputs.into_iter();
+        let benchmarks = SuiteBenchmarks {
+            list: &this.benchmark_list,
+            suite_config: config,
+        };
+        match this.axes {
+            Some(describe) => describe(&inputs, &mut |axes: &[Axis<'_>]| {
+                let hint = inputs.size_hint();
+                r.starting_new_benchmark_suite(this.name, hint, benchmarks.clone(), axes)
+            }),
+            None => r.starting_new_benchmark_suite(this.name, inputs.size_hint(), benchmarks, &[]),
+        }
 
-        let inputs = this.inputs.into_iter();
-        r.starting_new_benchmark_suite(
-            this.name,
-            inputs.size_hint(),
-            HListIterator(&this.benchmark_list as _),
-        );
+        let mut inp_idx = 0;
+        while let Some(inp) = inputs.next() {
+            if let Some(describe) = this.axes {
+                describe(&inputs, &mut |axes: &[Axis<'_>]| {
+                    r.suite_input_axes(inp_idx, axes)
+                });
+            }
 
-        for (inp_idx, inp) in inputs.enumerate() {
-            this.benchmark_list.run(m, r, iterations, inp_idx, &inp, 0);
+            this.benchmark_list.run(m, r, &config, inp_idx, &inp, 0);
+            inp_idx += 1;
         }
 
         r.ending_benchmark_suite(this.name);
 
-        rest.run(m, r, iterations);
+        rest.run(m, r, defaults);
     }
 
     fn name_and_next(&self) -> Option<(&'static str, &dyn RunnableBenchmarkList)> {
@@ -1,3 +1,8 @@
+use core::{any::Any, fmt::Debug};
+
+use super::{BatchSize, Config};
+use crate::{metrics::RawUnit, Benchmark, Metric};
+
 #[derive(Debug, Copy)]
 #[doc(hidden)]
 pub struct HListIterator<'a, Inner: ?Sized>(pub &'a Inner);
@@ -16,3 +21,206 @@ pub fn black_box<T>(x: T) -> T {
         ret
     }
 }
+
+// The message of a panic caught by `isolate`.
+#[cfg(feature = "std")]
+pub(super) struct Panic(std::boxed::Box<dyn Any + Send>);
+
+#[cfg(feature = "std")]
+impl Panic {
+    pub(super) fn message(&self) -> &str {
+        if let Some(s) = self.0.downcast_ref::<&'static str>() {
+            s
+        } else if let Some(s) = self.0.downcast_ref::<std::string::String>() {
+            s
+        } else {
+            "<non-string panic payload>"
+        }
+    }
+}
+
+// Without `std` panics cannot be caught.
+#[cfg(not(feature = "std"))]
+pub(super) enum Panic {}
+
+#[cfg(not(feature = "std"))]
+impl Panic {
+    pub(super) fn message(&self) -> &str {
+        match *self {}
+    }
+}
+
+// Runs `f`, catching panics if `config` asks for it.
+pub(super) fn isolate(config: &Config, f: impl FnOnce()) -> Result<(), Panic> {
+    #[cfg(feature = "std")]
+    if config.catching_panics() {
+        return std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).map_err(Panic);
+    }
+
+    let _ = config;
+    f();
+    Ok(())
+}
+
+// Runs `benchmark` on `inp` as many times as `config` asks for, handing the
+// index, the number of iterations being run (which is less than what `config`
+// asks for if it doesn't fit in the budget), and the measurement of each
+// measured iteration to `report`.
+//
+// The first measured iteration whose result fails verification also gets the
+// failure message; later failures for the same input are not reported.
+pub(super) fn run_iterations<Inp, B, M>(
+    benchmark: &mut B,
+    m: &mut M,
+    inp: &Inp,
+    config: &Config,
+    mut report: impl FnMut(usize, usize, M::Unit, Option<&'static str>),
+) where
+    Inp: Any + Debug,
+    B: Benchmark<Inp>,
+    M: Metric,
+{
+    for _ in 0..config.num_warmup_iterations() {
+        benchmark.setup(inp);
+        let res = black_box(benchmark.run(black_box(inp)));
+        benchmark.teardown(inp, res);
+    }
+
+    let batch_size = match config.batching() {
+        _ if !B::BATCHABLE => 1,
+        BatchSize::Fixed(n) => fit_divisor::<M>(n),
+        BatchSize::Auto => pick_batch_size(benchmark, m, inp),
+    };
+
+    let iterations = match config.budgeted() {
+        Some(budget) => {
+            let (measurement, _) = run_batch(benchmark, m, inp, batch_size);
+            let raw = measurement.to_raw().max(1);
+            (budget / raw).clamp(1, config.num_iterations() as u64) as usize
+        }
+        None => config.num_iterations(),
+    };
+
+    let mut failed = false;
+    for it_idx in 0..iterations {
+        let (measurement, verified) = run_batch(benchmark, m, inp, batch_size);
+        let measurement = if batch_size == 1 {
+            measurement
+        } else {
+            // Can't fail; see `fit_divisor`.
+            let count: M::Divisor = batch_size.try_into().map_err(|_| ()).unwrap();
+            measurement / count
+        };
+
+        let failure = match verified {
+            Err(msg) if !failed => {
+                failed = true;
+                Some(msg)
+            }
+            _ => None,
+        };
+
+        report(it_idx, iterations, measurement, failure);
+    }
+}
+
+// Measures `batch_size` back-to-back runs of `benchmark` and verifies the
+// result of the last one.
+#[inline(always)]
+fn run_batch<Inp, B, M>(
+    benchmark: &mut B,
+    m: &mut M,
+    inp: &Inp,
+    batch_size: usize,
+) -> (M::Unit, Result<(), &'static str>)
+where
+    Inp: Any + Debug,
+    B: Benchmark<Inp>,
+    M: Metric,
+{
+    benchmark.setup(inp);
+    let before = m.start();
+    let mut res = black_box(benchmark.run(black_box(inp)));
+    for _ in 1..batch_size {
+        res = black_box(benchmark.run(black_box(inp)));
+    }
+    let measurement = m.end(before);
+    let verified = benchmark.verify(inp, &res);
+    benchmark.teardown(inp, res);
+
+    (measurement, verified)
+}
+
+// The largest batch size (up to `batch_size`) that fits in an `M::Divisor`,
+// so that measurements can be divided by it.
+fn fit_divisor<M: Metric>(batch_size: usize) -> usize {
+    let fits = |n: usize| M::Divisor::try_from(n).is_ok();
+    if fits(batch_size) {
+        return batch_size;
+    }
+
+    // `fits(lo)` and `!fits(hi)`:
+    let (mut lo, mut hi) = (1, batch_size);
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if fits(mid) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+// For `BatchSize::Auto`: doubles the batch size until a batch takes at least
+// `FACTOR` times as long as an empty measurement (which we take to be the
+// resolution + overhead of the metric).
+//
+// If the measurement doesn't grow for `STALLS` doublings in a row (i.e. a
+// metric that always reads the same thing) this gives up and goes back to the
+// batch size of the last measurement that grew.
+//
+// The results of these runs aren't verified.
+fn pick_batch_size<Inp, B, M>(benchmark: &mut B, m: &mut M, inp: &Inp) -> usize
+where
+    Inp: Any + Debug,
+    B: Benchmark<Inp>,
+    M: Metric,
+{
+    const FACTOR: usize = 100;
+    const STALLS: usize = 8;
+
+    let empty = {
+        let before = m.start();
+        m.end(black_box(before))
+    };
+    let max = fit_divisor::<M>(BatchSize::MAX_AUTO);
+
+    let (mut batch_size, mut grew_at, mut stalls) = (1, 1, 0);
+    let mut last = None;
+    while batch_size < max {
+        let (measurement, _) = run_batch(benchmark, m, inp, batch_size);
+        let factor: M::Divisor = FACTOR.try_into().map_err(|_| ()).unwrap();
+        if measurement / factor > empty {
+            break;
+        }
+
+        match last {
+            Some(l) if measurement <= l => {
+                stalls += 1;
+                if stalls == STALLS {
+                    return grew_at;
+                }
+            }
+            _ => {
+                last = Some(measurement);
+                grew_at = batch_size;
+                stalls = 0;
+            }
+        }
+
+        batch_size = (batch_size * 2).min(max);
+    }
+
+    batch_size
+}